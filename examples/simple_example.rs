@@ -1,4 +1,5 @@
 use pumpdotfun_sdk::{
+    amount::TokenAmount,
     instructions::{
         buy::{Buy, BuyAccounts},
         create::{CreateAccounts, CreateArgs},
@@ -121,11 +122,12 @@ fn buy_simple_tokens(
         user: user_keypair.pubkey(),
     };
 
-    let args = Buy {
-        amount: 100_000_000,                   // 0.1 tokens (assuming 9 decimals)
-        max_sol_cost: LAMPORTS_PER_SOL / 1000, // Max 0.001 SOL
-        slippage: 10,                          // 10% slippage
-    };
+    let args = Buy::new(
+        TokenAmount::pump(100_000_000), // 100 tokens (pump mints use 6 decimals)
+        LAMPORTS_PER_SOL / 1000,        // Max 0.001 SOL
+        10,                             // 10% slippage
+    )
+    .map_err(|e| format!("Buy error: {:?}", e))?;
 
     let instructions = sdk
         .buy(accounts, args)
@@ -151,11 +153,12 @@ fn sell_simple_tokens(
         user: user_keypair.pubkey(),
     };
 
-    let args = Sell {
-        amount: 50_000_000,                         // 0.05 tokens (assuming 9 decimals)
-        min_sol_output: LAMPORTS_PER_SOL / 1000000, 
-        slippage: 10,                               // 10% slippage
-    };
+    let args = Sell::new(
+        TokenAmount::pump(50_000_000), // 50 tokens (pump mints use 6 decimals)
+        LAMPORTS_PER_SOL / 1000000,
+        10, // 10% slippage
+    )
+    .map_err(|e| format!("Sell error: {:?}", e))?;
 
     let instructions = sdk
         .sell(accounts, args)
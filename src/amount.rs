@@ -0,0 +1,234 @@
+use std::fmt;
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use spl_token::{solana_program::program_pack::Pack, state::Mint};
+
+use crate::{constants::TOKEN_DECIMALS, errors::ErrorCode};
+
+/// Decimals of SOL, i.e. lamports per SOL as a power of ten.
+pub const SOL_DECIMALS: u8 = 9;
+
+/// Suffixes accepted and produced for thousands, millions, billions and trillions.
+const SUFFIXES: [(char, u32); 4] = [('T', 12), ('B', 9), ('M', 6), ('K', 3)];
+
+/// A raw token amount together with the decimals of its mint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TokenAmount {
+    raw: u64,
+    decimals: u8,
+}
+
+impl TokenAmount {
+    pub fn new(raw: u64, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// An amount of a pump.fun mint, which always has [`TOKEN_DECIMALS`].
+    pub fn pump(raw: u64) -> Self {
+        Self::new(raw, TOKEN_DECIMALS)
+    }
+
+    /// Parses a UI amount such as `"1.5M"`, `"250k"` or `"1,000.25"`.
+    pub fn from_ui(amount: &str, decimals: u8) -> Result<Self, ErrorCode> {
+        Ok(Self::new(parse_ui_amount(amount, decimals)?, decimals))
+    }
+
+    pub fn raw(&self) -> u64 {
+        self.raw
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// The exact UI amount without trailing zeros, e.g. `"1500000.25"`.
+    pub fn to_ui_string(&self) -> String {
+        format_decimal(self.raw as u128, self.decimals as u32)
+    }
+
+    /// A short UI amount such as `"1.5M"`, truncated to two decimals.
+    pub fn to_compact_string(&self) -> String {
+        // Beyond 38 decimals no u64 reaches a hundredth of a token.
+        let Some(unit) = 10u128.checked_pow(self.decimals as u32) else {
+            return "0".to_string();
+        };
+        let whole = self.raw as u128 / unit;
+        for (suffix, exponent) in SUFFIXES {
+            let scale = 10u128.pow(exponent);
+            if whole >= scale {
+                let hundredths = self.raw as u128 * 100 / (unit * scale);
+                return format!("{}{}", format_decimal(hundredths, 2), suffix);
+            }
+        }
+        format_decimal(self.raw as u128 * 100 / unit, 2)
+    }
+
+    /// Reads the decimals of `mint` from its mint account.
+    pub fn fetch_decimals(rpc_client: &RpcClient, mint: &Pubkey) -> Result<u8, ErrorCode> {
+        let data = rpc_client
+            .get_account_data(mint)
+            .map_err(|_| ErrorCode::AccountFetchFailed)?;
+        // Token-2022 mints carry extensions after the base layout.
+        let base = data
+            .get(..Mint::LEN)
+            .ok_or(ErrorCode::DeserializationError)?;
+        Mint::unpack(base)
+            .map(|mint| mint.decimals)
+            .map_err(|_| ErrorCode::DeserializationError)
+    }
+
+    /// Errors unless the amount uses the decimals of pump.fun mints.
+    pub fn pump_raw(&self) -> Result<u64, ErrorCode> {
+        if self.decimals != TOKEN_DECIMALS {
+            return Err(ErrorCode::DecimalsMismatch);
+        }
+        Ok(self.raw)
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_ui_string())
+    }
+}
+
+/// Converts a SOL amount such as `"0.001"` to lamports exactly, without
+/// going through a float.
+pub fn sol_to_lamports(sol: &str) -> Result<u64, ErrorCode> {
+    parse_ui_amount(sol, SOL_DECIMALS)
+}
+
+pub fn lamports_to_sol_string(lamports: u64) -> String {
+    format_decimal(lamports as u128, SOL_DECIMALS as u32)
+}
+
+/// Parses a decimal string with an optional K/M/B/T suffix into base units
+/// of a token with `decimals`. Separators `,` and `_` are ignored. Amounts
+/// more precise than the token allows are rejected rather than rounded, as
+/// are decimals too large for any amount to be represented.
+pub fn parse_ui_amount(amount: &str, decimals: u8) -> Result<u64, ErrorCode> {
+    let cleaned: String = amount
+        .trim()
+        .chars()
+        .filter(|c| *c != ',' && *c != '_')
+        .collect();
+    let (number, exponent) = match cleaned.chars().last() {
+        Some(last) if last.is_ascii_alphabetic() => {
+            let (_, exponent) = SUFFIXES
+                .iter()
+                .find(|(suffix, _)| last.eq_ignore_ascii_case(suffix))
+                .ok_or(ErrorCode::InvalidAmount)?;
+            (&cleaned[..cleaned.len() - 1], *exponent)
+        }
+        _ => (cleaned.as_str(), 0),
+    };
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(ErrorCode::InvalidAmount);
+    }
+    let scale = decimals as u32 + exponent;
+    let unit = 10u128.checked_pow(scale).ok_or(ErrorCode::InvalidAmount)?;
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > scale as usize {
+        return Err(ErrorCode::InvalidAmount);
+    }
+
+    let parse = |digits: &str| -> Result<u128, ErrorCode> {
+        if digits.is_empty() {
+            return Ok(0);
+        }
+        digits.parse::<u128>().map_err(|_| ErrorCode::Overflow)
+    };
+    let whole = parse(whole)?.checked_mul(unit).ok_or(ErrorCode::Overflow)?;
+    let fraction = parse(fraction)? * 10u128.pow(scale - fraction.len() as u32);
+    let total = whole.checked_add(fraction).ok_or(ErrorCode::Overflow)?;
+    u64::try_from(total).map_err(|_| ErrorCode::Overflow)
+}
+
+fn format_decimal(value: u128, decimals: u32) -> String {
+    // A unit past u128 is larger than any value, so it is all fraction.
+    let (whole, fraction) = match 10u128.checked_pow(decimals) {
+        Some(unit) => (value / unit, value % unit),
+        None => (0, value),
+    };
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ui_amount() {
+        assert_eq!(parse_ui_amount("1.5M", 6).unwrap(), 1_500_000_000_000);
+        assert_eq!(parse_ui_amount(" 250k ", 6).unwrap(), 250_000_000_000);
+        assert_eq!(parse_ui_amount("1,000.25", 6).unwrap(), 1_000_250_000);
+        assert_eq!(parse_ui_amount(".5", 6).unwrap(), 500_000);
+        assert_eq!(parse_ui_amount("0.000001", 6).unwrap(), 1);
+        assert!(matches!(
+            parse_ui_amount("0.0000001", 6),
+            Err(ErrorCode::InvalidAmount)
+        ));
+        assert!(matches!(
+            parse_ui_amount("1.5X", 6),
+            Err(ErrorCode::InvalidAmount)
+        ));
+        assert!(matches!(
+            parse_ui_amount("-1", 6),
+            Err(ErrorCode::InvalidAmount)
+        ));
+        assert!(matches!(
+            parse_ui_amount(".", 6),
+            Err(ErrorCode::InvalidAmount)
+        ));
+        assert!(matches!(
+            parse_ui_amount("20T", 9),
+            Err(ErrorCode::Overflow)
+        ));
+        assert!(matches!(
+            parse_ui_amount("1", 39),
+            Err(ErrorCode::InvalidAmount)
+        ));
+        // 3 * 10^38 fits a u128, adding the fraction does not.
+        assert!(matches!(
+            parse_ui_amount("3.9", 38),
+            Err(ErrorCode::Overflow)
+        ));
+        assert_eq!(TokenAmount::new(5, u8::MAX).to_compact_string(), "0");
+        assert!(TokenAmount::new(5, 40).to_ui_string().ends_with('5'));
+    }
+
+    #[test]
+    fn test_sol_to_lamports_is_exact() {
+        // 0.1 + 0.2 style inputs that a float would round.
+        assert_eq!(sol_to_lamports("0.3").unwrap(), 300_000_000);
+        assert_eq!(sol_to_lamports("1.000000001").unwrap(), 1_000_000_001);
+        assert_eq!(sol_to_lamports("18446744073.709551615").unwrap(), u64::MAX);
+        assert_eq!(lamports_to_sol_string(1_000_000_001), "1.000000001");
+    }
+
+    #[test]
+    fn test_format_token_amount() {
+        let amount = TokenAmount::from_ui("1.5M", TOKEN_DECIMALS).unwrap();
+        assert_eq!(amount, TokenAmount::pump(1_500_000_000_000));
+        assert_eq!(amount.to_ui_string(), "1500000");
+        assert_eq!(amount.to_compact_string(), "1.5M");
+        assert_eq!(TokenAmount::pump(999_999_999).to_compact_string(), "999.99");
+        assert_eq!(TokenAmount::pump(1_234_567_891).to_string(), "1234.567891");
+        assert_eq!(
+            TokenAmount::pump(1_239_000_000).to_compact_string(),
+            "1.23K"
+        );
+        assert!(matches!(
+            TokenAmount::new(1, 9).pump_raw(),
+            Err(ErrorCode::DecimalsMismatch)
+        ));
+    }
+}
@@ -0,0 +1,295 @@
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+    signer::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use pumpdotfun_sdk::{
+    amount::{lamports_to_sol_string, sol_to_lamports, TokenAmount},
+    constants::TOKEN_DECIMALS,
+    fetch::{fetch_bonding_curve, fetch_global, fetch_token_balance, fetch_token_program},
+    pda::{get_bonding_curve_pda, get_creator_vault_pda},
+    states::BondingCurve,
+    Buy, BuyAccounts, CreateAccounts, CreateArgs, PumpDotFunSdk, Sell, SellAccounts,
+};
+
+use crate::{CliError, Side};
+
+pub struct Context {
+    pub sdk: PumpDotFunSdk,
+    pub keypair_path: Option<PathBuf>,
+    pub priority_fee: Option<u64>,
+    pub dry_run: bool,
+}
+
+fn read_keypair(path: &PathBuf) -> Result<Keypair, CliError> {
+    read_keypair_file(path).map_err(|err| {
+        CliError(format!(
+            "failed to read keypair {}: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+fn token_string(raw: u64) -> String {
+    TokenAmount::pump(raw).to_ui_string()
+}
+
+impl Context {
+    fn payer(&self) -> Result<Keypair, CliError> {
+        let path = self
+            .keypair_path
+            .as_ref()
+            .ok_or_else(|| CliError("no keypair given and HOME is not set".to_string()))?;
+        read_keypair(path)
+    }
+
+    /// Signs `instructions` with the payer first in `signers`, then sends
+    /// them, or only simulates them with `--dry-run`.
+    fn submit(
+        &self,
+        instructions: Vec<Instruction>,
+        signers: &[&dyn Signer],
+    ) -> Result<Value, CliError> {
+        let rpc = &self.sdk.rpc;
+        let payer = signers[0].pubkey();
+
+        let mut all = vec![];
+        if let Some(micro_lamports) = self.priority_fee {
+            all.push(ComputeBudgetInstruction::set_compute_unit_price(
+                micro_lamports,
+            ));
+        }
+        all.extend(instructions);
+
+        let recent_blockhash = rpc.get_latest_blockhash()?;
+        let mut transaction = Transaction::new_with_payer(&all, Some(&payer));
+        transaction.try_sign(signers, recent_blockhash)?;
+
+        if self.dry_run {
+            let result = rpc.simulate_transaction(&transaction)?.value;
+            return Ok(json!({
+                "simulated": true,
+                "error": result.err.map(|err| err.to_string()),
+                "units_consumed": result.units_consumed,
+                "logs": result.logs.unwrap_or_default(),
+            }));
+        }
+        let signature = rpc.send_and_confirm_transaction(&transaction)?;
+        Ok(json!({ "signature": signature.to_string() }))
+    }
+
+    fn with_transaction(mut details: Value, transaction: Value) -> Value {
+        if let (Value::Object(details), Value::Object(transaction)) = (&mut details, transaction) {
+            details.extend(transaction);
+        }
+        details
+    }
+
+    fn curve_and_fee(&self, mint: &Pubkey) -> Result<(BondingCurve, u64), CliError> {
+        let global = fetch_global(&self.sdk.rpc, &self.sdk.config)?;
+        let curve = fetch_bonding_curve(&self.sdk.rpc, &self.sdk.config, mint)?;
+        let fee_basis_points = global.trade_fee_basis_points(&curve.creator);
+        Ok((curve, fee_basis_points))
+    }
+
+    pub fn create(
+        &self,
+        name: String,
+        symbol: String,
+        uri: String,
+        mint_keypair: Option<PathBuf>,
+        token_2022: bool,
+    ) -> Result<Value, CliError> {
+        let payer = self.payer()?;
+        let mint = match mint_keypair {
+            Some(path) => read_keypair(&path)?,
+            None => Keypair::new(),
+        };
+        let accounts = CreateAccounts {
+            mint: mint.pubkey(),
+            user: payer.pubkey(),
+        };
+        let args = CreateArgs {
+            name,
+            symbol,
+            uri,
+            creator: payer.pubkey(),
+        };
+        let instruction = if token_2022 {
+            self.sdk.create_v2(accounts, args)
+        } else {
+            self.sdk.create(accounts, args)
+        };
+        let sent = self.submit(vec![instruction], &[&payer, &mint])?;
+        Ok(Self::with_transaction(
+            json!({
+                "mint": mint.pubkey().to_string(),
+                "bonding_curve": get_bonding_curve_pda(&self.sdk.config, &mint.pubkey()).to_string(),
+            }),
+            sent,
+        ))
+    }
+
+    pub fn buy(&self, mint: &Pubkey, sol: &str, slippage: i64) -> Result<Value, CliError> {
+        let payer = self.payer()?;
+        let lamports = sol_to_lamports(sol)?;
+        let (curve, fee_basis_points) = self.curve_and_fee(mint)?;
+        let tokens = curve.buy_quote(lamports, fee_basis_points);
+
+        let instructions = self.sdk.buy(
+            BuyAccounts {
+                mint: *mint,
+                user: payer.pubkey(),
+            },
+            Buy::new(TokenAmount::pump(tokens), lamports, slippage)?,
+        )?;
+        let sent = self.submit(instructions, &[&payer])?;
+        Ok(Self::with_transaction(
+            json!({
+                "mint": mint.to_string(),
+                "tokens": token_string(tokens),
+                "sol": lamports_to_sol_string(lamports),
+            }),
+            sent,
+        ))
+    }
+
+    /// Sells `amount` tokens, or the whole balance when `amount` is `None`.
+    pub fn sell(
+        &self,
+        mint: &Pubkey,
+        amount: Option<&str>,
+        slippage: i64,
+    ) -> Result<Value, CliError> {
+        let payer = self.payer()?;
+        let amount = match amount {
+            Some(amount) => TokenAmount::from_ui(amount, TOKEN_DECIMALS)?,
+            None => {
+                let token_program = fetch_token_program(&self.sdk.rpc, mint)?;
+                let token_account = get_associated_token_address_with_program_id(
+                    &payer.pubkey(),
+                    mint,
+                    &token_program,
+                );
+                let balance = fetch_token_balance(&self.sdk.rpc, &token_account)?;
+                if balance == 0 {
+                    return Err(CliError(format!("no {} tokens to sell", mint)));
+                }
+                TokenAmount::pump(balance)
+            }
+        };
+        let (curve, fee_basis_points) = self.curve_and_fee(mint)?;
+        let min_sol_output = curve.sell_quote(amount.pump_raw()?, fee_basis_points)?;
+
+        let instructions = self.sdk.sell(
+            SellAccounts {
+                mint: *mint,
+                user: payer.pubkey(),
+            },
+            Sell::new(amount, min_sol_output, slippage)?,
+        )?;
+        let sent = self.submit(instructions, &[&payer])?;
+        Ok(Self::with_transaction(
+            json!({
+                "mint": mint.to_string(),
+                "tokens": amount.to_ui_string(),
+                "expected_sol": lamports_to_sol_string(min_sol_output),
+            }),
+            sent,
+        ))
+    }
+
+    pub fn collect_fees(&self) -> Result<Value, CliError> {
+        let payer = self.payer()?;
+        let vault = get_creator_vault_pda(&self.sdk.config, &payer.pubkey());
+        let balance = self.sdk.rpc.get_balance(&vault)?;
+        let rent = self.sdk.rpc.get_minimum_balance_for_rent_exemption(0)?;
+        let claimable = balance.saturating_sub(rent);
+
+        let details = json!({
+            "creator_vault": vault.to_string(),
+            "claimable_sol": lamports_to_sol_string(claimable),
+        });
+        if claimable == 0 {
+            return Ok(details);
+        }
+        let sent = self.submit(
+            vec![self.sdk.collect_creator_fee(&payer.pubkey())],
+            &[&payer],
+        )?;
+        Ok(Self::with_transaction(details, sent))
+    }
+
+    pub fn curve(&self, mint: &Pubkey) -> Result<Value, CliError> {
+        let curve = fetch_bonding_curve(&self.sdk.rpc, &self.sdk.config, mint)?;
+        Ok(json!({
+            "mint": mint.to_string(),
+            "bonding_curve": get_bonding_curve_pda(&self.sdk.config, mint).to_string(),
+            "creator": curve.creator.to_string(),
+            "complete": curve.complete,
+            "price_sol": curve.price_in_sol(),
+            "progress_percent": curve.progress() * 100.0,
+            "virtual_token_reserves": curve.virtual_token_reserves,
+            "virtual_sol_reserves": curve.virtual_sol_reserves,
+            "real_token_reserves": curve.real_token_reserves,
+            "real_sol_reserves": curve.real_sol_reserves,
+            "token_total_supply": curve.token_total_supply,
+        }))
+    }
+
+    pub fn global(&self) -> Result<Value, CliError> {
+        let global = fetch_global(&self.sdk.rpc, &self.sdk.config)?;
+        Ok(json!({
+            "initialized": global.initialized,
+            "authority": global.authority.to_string(),
+            "fee_recipient": global.fee_recipient.to_string(),
+            "fee_basis_points": global.fee_basis_points,
+            "creator_fee_basis_points": global.creator_fee_basis_points,
+            "initial_virtual_token_reserves": global.initial_virtual_token_reserves,
+            "initial_virtual_sol_reserves": global.initial_virtual_sol_reserves,
+            "initial_real_token_reserves": global.initial_real_token_reserves,
+            "token_total_supply": global.token_total_supply,
+            "pool_migration_fee_sol": lamports_to_sol_string(global.pool_migration_fee),
+            "enable_migrate": global.enable_migrate,
+        }))
+    }
+
+    pub fn quote(&self, mint: &Pubkey, side: Side, amount: &str) -> Result<Value, CliError> {
+        let (curve, fee_basis_points) = self.curve_and_fee(mint)?;
+        let (sol, tokens) = match side {
+            Side::Buy => {
+                let lamports = sol_to_lamports(amount)?;
+                (lamports, curve.buy_quote(lamports, fee_basis_points))
+            }
+            Side::Sell => {
+                let tokens = TokenAmount::from_ui(amount, TOKEN_DECIMALS)?.raw();
+                (curve.sell_quote(tokens, fee_basis_points)?, tokens)
+            }
+        };
+        let average_price = if tokens == 0 {
+            0.0
+        } else {
+            (sol as f64 / LAMPORTS_PER_SOL as f64)
+                / (tokens as f64 / 10f64.powi(TOKEN_DECIMALS as i32))
+        };
+        Ok(json!({
+            "mint": mint.to_string(),
+            "side": match side { Side::Buy => "buy", Side::Sell => "sell" },
+            "sol": lamports_to_sol_string(sol),
+            "tokens": token_string(tokens),
+            "average_price_sol": average_price,
+            "spot_price_sol": curve.price_in_sol(),
+            "fee_basis_points": fee_basis_points,
+        }))
+    }
+}
@@ -0,0 +1,102 @@
+use std::{fs, path::PathBuf};
+
+use serde::Deserialize;
+
+use crate::CliError;
+
+/// The subset of the Solana CLI config file (`solana config get`) used here.
+#[derive(Debug, Default, Deserialize)]
+pub struct SolanaConfig {
+    #[serde(default)]
+    pub json_rpc_url: Option<String>,
+    #[serde(default)]
+    pub keypair_path: Option<String>,
+    #[serde(default)]
+    pub commitment: Option<String>,
+}
+
+/// Where the Solana CLI keeps its config unless told otherwise.
+pub fn default_config_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| {
+        PathBuf::from(home)
+            .join(".config")
+            .join("solana")
+            .join("cli")
+            .join("config.yml")
+    })
+}
+
+pub fn default_keypair_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| {
+        PathBuf::from(home)
+            .join(".config")
+            .join("solana")
+            .join("id.json")
+    })
+}
+
+impl SolanaConfig {
+    /// Loads `path`, or the default config if it exists. A missing default
+    /// config is not an error; a missing explicit one is.
+    pub fn load(path: Option<&PathBuf>) -> Result<Self, CliError> {
+        let (path, explicit) = match path {
+            Some(path) => (path.clone(), true),
+            None => match default_config_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents),
+            Err(_) if !explicit => Ok(Self::default()),
+            Err(err) => Err(CliError(format!(
+                "failed to read config {}: {}",
+                path.display(),
+                err
+            ))),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, CliError> {
+        serde_yaml::from_str(contents).map_err(|err| CliError(format!("invalid config: {}", err)))
+    }
+}
+
+/// Expands the cluster monikers the Solana CLI accepts for `--url`.
+pub fn normalize_url(url: &str) -> String {
+    match url {
+        "m" | "mainnet-beta" => "https://api.mainnet-beta.solana.com",
+        "d" | "devnet" => "https://api.devnet.solana.com",
+        "t" | "testnet" => "https://api.testnet.solana.com",
+        "l" | "localhost" => "http://localhost:8899",
+        url => url,
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_solana_cli_config() {
+        let config = SolanaConfig::parse(
+            "---\njson_rpc_url: https://api.devnet.solana.com\nwebsocket_url: ''\nkeypair_path: /home/me/.config/solana/id.json\naddress_labels:\n  '11111111111111111111111111111111': System Program\ncommitment: confirmed\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.json_rpc_url.as_deref(),
+            Some("https://api.devnet.solana.com")
+        );
+        assert_eq!(
+            config.keypair_path.as_deref(),
+            Some("/home/me/.config/solana/id.json")
+        );
+        assert_eq!(config.commitment.as_deref(), Some("confirmed"));
+        assert_eq!(normalize_url("d"), "https://api.devnet.solana.com");
+        assert_eq!(
+            normalize_url("http://127.0.0.1:8899"),
+            "http://127.0.0.1:8899"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! `pumpfun`: create, trade and inspect pump.fun tokens from the command line.
+
+mod commands;
+mod config;
+mod output;
+
+use std::{fmt::Display, path::PathBuf, process::ExitCode, str::FromStr, sync::Arc};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+
+use config::{default_keypair_path, normalize_url, SolanaConfig};
+use output::{render, OutputFormat};
+use pumpdotfun_sdk::{config::PumpConfig, PumpDotFunSdk};
+
+/// An error reported to the user; any displayable error converts into one.
+#[derive(Debug)]
+pub struct CliError(pub String);
+
+impl<E: Display> From<E> for CliError {
+    fn from(err: E) -> Self {
+        Self(err.to_string())
+    }
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "pumpfun",
+    version,
+    about = "Create, trade and inspect pump.fun tokens"
+)]
+struct Cli {
+    /// Solana CLI config file [default: ~/.config/solana/cli/config.yml]
+    #[arg(short = 'C', long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// RPC URL or moniker (mainnet-beta, devnet, testnet, localhost)
+    #[arg(short = 'u', long, global = true, value_name = "URL")]
+    url: Option<String>,
+
+    /// Keypair that signs and pays [default: from config, else ~/.config/solana/id.json]
+    #[arg(short = 'k', long, global = true, value_name = "PATH")]
+    keypair: Option<PathBuf>,
+
+    #[arg(long, global = true, value_enum)]
+    commitment: Option<Commitment>,
+
+    /// pump.fun program to target instead of the mainnet deployment
+    #[arg(long, global = true, value_name = "PUBKEY", value_parser = parse_pubkey)]
+    program_id: Option<Pubkey>,
+
+    /// Compute unit price in micro-lamports added to every transaction
+    #[arg(long, global = true, value_name = "MICRO_LAMPORTS")]
+    priority_fee: Option<u64>,
+
+    /// Simulate transactions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    #[arg(long, global = true, value_enum, default_value = "display")]
+    output: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Launch a new token on a bonding curve
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+        /// Metadata JSON URI
+        #[arg(long)]
+        uri: String,
+        /// Keypair for the new mint [default: a fresh one]
+        #[arg(long, value_name = "PATH")]
+        mint_keypair: Option<PathBuf>,
+        /// Create a Token-2022 mint instead of an SPL Token one
+        #[arg(long)]
+        token_2022: bool,
+    },
+    /// Buy tokens with SOL
+    Buy {
+        #[arg(value_parser = parse_pubkey)]
+        mint: Pubkey,
+        /// SOL to spend, e.g. 0.1
+        sol: String,
+        /// Slippage tolerance in percent
+        #[arg(long, default_value_t = 10)]
+        slippage: i64,
+    },
+    /// Sell tokens for SOL
+    Sell {
+        #[arg(value_parser = parse_pubkey)]
+        mint: Pubkey,
+        /// Tokens to sell, e.g. 250000 or 1.5M
+        amount: String,
+        /// Slippage tolerance in percent
+        #[arg(long, default_value_t = 10)]
+        slippage: i64,
+    },
+    /// Sell the whole token balance
+    SellAll {
+        #[arg(value_parser = parse_pubkey)]
+        mint: Pubkey,
+        /// Slippage tolerance in percent
+        #[arg(long, default_value_t = 10)]
+        slippage: i64,
+    },
+    /// Claim creator fees accrued by the keypair's launches
+    CollectFees,
+    /// Show a token's bonding curve
+    Curve {
+        #[arg(value_parser = parse_pubkey)]
+        mint: Pubkey,
+    },
+    /// Show the program's global configuration
+    Global,
+    /// Price a trade without sending it
+    Quote {
+        #[arg(value_parser = parse_pubkey)]
+        mint: Pubkey,
+        #[arg(value_enum)]
+        side: Side,
+        /// SOL to spend for buys, tokens to sell for sells
+        amount: String,
+    },
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(value).map_err(|err| format!("invalid pubkey: {}", err))
+}
+
+fn run(cli: Cli) -> Result<serde_json::Value, CliError> {
+    let config = SolanaConfig::load(cli.config.as_ref())?;
+
+    let url = cli
+        .url
+        .or(config.json_rpc_url)
+        .map(|url| normalize_url(&url))
+        .unwrap_or_else(|| normalize_url("mainnet-beta"));
+    let commitment = match (cli.commitment, config.commitment.as_deref()) {
+        (Some(Commitment::Processed), _) => CommitmentConfig::processed(),
+        (Some(Commitment::Finalized), _) => CommitmentConfig::finalized(),
+        (Some(Commitment::Confirmed), _) => CommitmentConfig::confirmed(),
+        (None, Some(level)) => CommitmentConfig::from_str(level)
+            .map_err(|_| CliError(format!("invalid commitment in config: {}", level)))?,
+        (None, None) => CommitmentConfig::confirmed(),
+    };
+    let keypair_path = cli
+        .keypair
+        .or(config.keypair_path.map(PathBuf::from))
+        .or_else(default_keypair_path);
+
+    let mut pump_config = PumpConfig::mainnet();
+    if let Some(program_id) = cli.program_id {
+        pump_config = pump_config.with_program_id(program_id);
+    }
+
+    let context = commands::Context {
+        sdk: PumpDotFunSdk::with_config(
+            Arc::new(RpcClient::new_with_commitment(url, commitment)),
+            pump_config,
+        ),
+        keypair_path,
+        priority_fee: cli.priority_fee,
+        dry_run: cli.dry_run,
+    };
+
+    match cli.command {
+        Command::Create {
+            name,
+            symbol,
+            uri,
+            mint_keypair,
+            token_2022,
+        } => context.create(name, symbol, uri, mint_keypair, token_2022),
+        Command::Buy {
+            mint,
+            sol,
+            slippage,
+        } => context.buy(&mint, &sol, slippage),
+        Command::Sell {
+            mint,
+            amount,
+            slippage,
+        } => context.sell(&mint, Some(&amount), slippage),
+        Command::SellAll { mint, slippage } => context.sell(&mint, None, slippage),
+        Command::CollectFees => context.collect_fees(),
+        Command::Curve { mint } => context.curve(&mint),
+        Command::Global => context.global(),
+        Command::Quote { mint, side, amount } => context.quote(&mint, side, &amount),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let format = cli.output;
+    match run(cli) {
+        Ok(value) => {
+            println!("{}", render(&value, format));
+            ExitCode::SUCCESS
+        }
+        Err(CliError(message)) => {
+            match format {
+                OutputFormat::Json => println!("{}", json!({ "error": message })),
+                OutputFormat::Display => eprintln!("error: {}", message),
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "pumpfun",
+            "buy",
+            "11111111111111111111111111111111",
+            "0.25",
+            "--dry-run",
+            "--output",
+            "json",
+            "-u",
+            "devnet",
+        ])
+        .unwrap();
+        assert!(cli.dry_run);
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert!(matches!(cli.command, Command::Buy { slippage: 10, .. }));
+        assert!(Cli::try_parse_from(["pumpfun", "curve", "not-a-pubkey"]).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use clap::ValueEnum;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned `key: value` lines.
+    Display,
+    /// One JSON object per command, for scripting.
+    Json,
+}
+
+pub fn render(value: &Value, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Json => value.to_string(),
+        OutputFormat::Display => {
+            let mut lines = vec![];
+            render_display(value, 0, &mut lines);
+            lines.join("\n")
+        }
+    }
+}
+
+fn render_display(value: &Value, indent: usize, lines: &mut Vec<String>) {
+    let pad = "  ".repeat(indent);
+    match value {
+        Value::Object(fields) => {
+            let width = fields.keys().map(|key| key.len()).max().unwrap_or(0);
+            for (key, field) in fields {
+                match field {
+                    Value::Object(_) | Value::Array(_) => {
+                        lines.push(format!("{}{}:", pad, key));
+                        render_display(field, indent + 1, lines);
+                    }
+                    _ => lines.push(format!(
+                        "{}{:width$}  {}",
+                        pad,
+                        format!("{}:", key),
+                        scalar(field),
+                        width = width + 1
+                    )),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                match item {
+                    Value::Object(_) | Value::Array(_) => render_display(item, indent, lines),
+                    _ => lines.push(format!("{}{}", pad, scalar(item))),
+                }
+            }
+        }
+        _ => lines.push(format!("{}{}", pad, scalar(value))),
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => "-".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_display_and_json() {
+        let value = json!({ "mint": "abc", "complete": false, "logs": ["a", "b"] });
+        assert_eq!(
+            render(&value, OutputFormat::Display),
+            "complete:  false\nlogs:\n  a\n  b\nmint:      abc"
+        );
+        assert_eq!(
+            render(&value, OutputFormat::Json),
+            r#"{"complete":false,"logs":["a","b"],"mint":"abc"}"#
+        );
+    }
+}
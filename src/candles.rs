@@ -0,0 +1,299 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::events::TradeEvent;
+
+/// Width of a candle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandleInterval {
+    OneSecond,
+    OneMinute,
+    FiveMinutes,
+    OneHour,
+}
+
+impl CandleInterval {
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Self::OneSecond => 1,
+            Self::OneMinute => 60,
+            Self::FiveMinutes => 300,
+            Self::OneHour => 3_600,
+        }
+    }
+
+    /// Start of the candle `timestamp` falls in.
+    pub fn open_time(&self, timestamp: i64) -> i64 {
+        timestamp.div_euclid(self.seconds()) * self.seconds()
+    }
+}
+
+/// Where an event sits in chain order: its slot, then the order it reached
+/// the builder, which breaks ties within a slot.
+type EventOrder = (u64, u64);
+
+/// A trade's transaction signature and its index among that transaction's
+/// trade events, which together identify it.
+type TradeId = (Signature, usize);
+
+/// OHLCV of one mint over one interval. Prices are in SOL per whole token
+/// after each trade; volumes are in lamports before fees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candle {
+    pub mint: Pubkey,
+    pub interval: CandleInterval,
+    /// Unix timestamp the candle starts at.
+    pub open_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Tokens traded, in raw units.
+    pub token_volume: u64,
+    pub buy_volume: u64,
+    pub sell_volume: u64,
+    pub trades: u64,
+    pub first_slot: u64,
+    pub last_slot: u64,
+    open_order: EventOrder,
+    close_order: EventOrder,
+}
+
+impl Candle {
+    fn new(trade: &TradeEvent, interval: CandleInterval, order: EventOrder) -> Self {
+        let price = trade.price_in_sol();
+        Self {
+            mint: trade.mint,
+            interval,
+            open_time: interval.open_time(trade.timestamp),
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            token_volume: 0,
+            buy_volume: 0,
+            sell_volume: 0,
+            trades: 0,
+            first_slot: order.0,
+            last_slot: order.0,
+            open_order: order,
+            close_order: order,
+        }
+    }
+
+    fn apply(&mut self, trade: &TradeEvent, order: EventOrder) {
+        let price = trade.price_in_sol();
+        // Open and close follow chain order, so a late event can still move
+        // them; high, low and volumes do not depend on order.
+        if order < self.open_order {
+            self.open = price;
+            self.open_order = order;
+        }
+        if order >= self.close_order {
+            self.close = price;
+            self.close_order = order;
+        }
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.token_volume += trade.token_amount;
+        if trade.is_buy {
+            self.buy_volume += trade.sol_amount;
+        } else {
+            self.sell_volume += trade.sol_amount;
+        }
+        self.trades += 1;
+        self.first_slot = self.first_slot.min(order.0);
+        self.last_slot = self.last_slot.max(order.0);
+    }
+
+    /// `buy_volume + sell_volume`.
+    pub fn volume(&self) -> u64 {
+        self.buy_volume + self.sell_volume
+    }
+}
+
+/// Aggregates `TradeEvent`s into per-mint candles of one interval. Events
+/// may arrive late or out of order across slots, from live logs or a
+/// backfill; each one is placed by its timestamp and ordered within the
+/// candle by slot. Trades of the same slot must be pushed in chain order,
+/// since nothing else tells them apart: a backfill that walks history newest
+/// first has to push each slot's trades reversed.
+#[derive(Debug)]
+pub struct CandleBuilder {
+    interval: CandleInterval,
+    candles: BTreeMap<(Pubkey, i64), Candle>,
+    /// Trades already applied to each open candle.
+    seen: HashMap<(Pubkey, i64), HashSet<TradeId>>,
+    /// Per mint, candles starting before this time have been handed out by
+    /// [`CandleBuilder::take_closed`].
+    closed_before: HashMap<Pubkey, i64>,
+    arrivals: u64,
+    dropped: u64,
+}
+
+impl CandleBuilder {
+    pub fn new(interval: CandleInterval) -> Self {
+        Self {
+            interval,
+            candles: BTreeMap::new(),
+            seen: HashMap::new(),
+            closed_before: HashMap::new(),
+            arrivals: 0,
+            dropped: 0,
+        }
+    }
+
+    pub fn interval(&self) -> CandleInterval {
+        self.interval
+    }
+
+    /// Adds the `event_index`-th trade event of transaction `signature`,
+    /// landed in `slot`. Returns `false` when that trade was already pushed,
+    /// so live logs and a backfill can overlap, or when its candle was
+    /// already taken, which is counted in [`CandleBuilder::dropped`].
+    pub fn push(
+        &mut self,
+        slot: u64,
+        signature: &Signature,
+        event_index: usize,
+        trade: &TradeEvent,
+    ) -> bool {
+        let open_time = self.interval.open_time(trade.timestamp);
+        if self
+            .closed_before
+            .get(&trade.mint)
+            .is_some_and(|closed| open_time < *closed)
+        {
+            self.dropped += 1;
+            return false;
+        }
+        let key = (trade.mint, open_time);
+        if !self
+            .seen
+            .entry(key)
+            .or_default()
+            .insert((*signature, event_index))
+        {
+            return false;
+        }
+
+        let order = (slot, self.arrivals);
+        self.arrivals += 1;
+        self.candles
+            .entry(key)
+            .or_insert_with(|| Candle::new(trade, self.interval, order))
+            .apply(trade, order);
+        true
+    }
+
+    /// Candles of `mint` still being built, oldest first.
+    pub fn candles(&self, mint: &Pubkey) -> Vec<&Candle> {
+        self.candles
+            .range((*mint, i64::MIN)..=(*mint, i64::MAX))
+            .map(|(_, candle)| candle)
+            .collect()
+    }
+
+    /// Removes and returns every candle that ends at or before `watermark`,
+    /// oldest first per mint. Pass the latest timestamp seen minus however
+    /// long late events may trail; events for those candles are dropped
+    /// from then on.
+    pub fn take_closed(&mut self, watermark: i64) -> Vec<Candle> {
+        let interval = self.interval.seconds();
+        let closed: Vec<(Pubkey, i64)> = self
+            .candles
+            .keys()
+            .filter(|(_, open_time)| open_time + interval <= watermark)
+            .copied()
+            .collect();
+
+        closed
+            .into_iter()
+            .filter_map(|key| {
+                self.seen.remove(&key);
+                let candle = self.candles.remove(&key)?;
+                let closed_before = self.closed_before.entry(candle.mint).or_insert(i64::MIN);
+                *closed_before = (*closed_before).max(candle.open_time + interval);
+                Some(candle)
+            })
+            .collect()
+    }
+
+    /// Trades rejected because their candle had already been taken.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::trade_event::tests::trade_event;
+
+    fn trade(mint: Pubkey, timestamp: i64, virtual_sol_reserves: u64, is_buy: bool) -> TradeEvent {
+        TradeEvent {
+            timestamp,
+            virtual_sol_reserves,
+            virtual_token_reserves: 1_000_000_000_000,
+            ..trade_event(mint, is_buy)
+        }
+    }
+
+    #[test]
+    fn test_out_of_order_events_are_placed_by_slot() {
+        let mint = Pubkey::new_unique();
+        let mut builder = CandleBuilder::new(CandleInterval::OneMinute);
+
+        let signatures: Vec<Signature> = (0..5).map(|_| Signature::new_unique()).collect();
+
+        // Reserves of 30, 40 and 35 SOL against a million tokens.
+        let late = trade(mint, 61, 30_000_000_000, true);
+        assert!(builder.push(
+            11,
+            &signatures[0],
+            0,
+            &trade(mint, 65, 40_000_000_000, true)
+        ));
+        assert!(builder.push(
+            12,
+            &signatures[1],
+            0,
+            &trade(mint, 70, 35_000_000_000, false)
+        ));
+        assert!(builder.push(10, &signatures[2], 0, &late));
+        assert!(builder.push(
+            20,
+            &signatures[3],
+            0,
+            &trade(mint, 125, 36_000_000_000, true)
+        ));
+        // The same trade again, e.g. from a backfill overlapping live logs.
+        assert!(!builder.push(10, &signatures[2], 0, &late));
+
+        let candles = builder.candles(&mint);
+        assert_eq!(candles.len(), 2);
+        let first = candles[0];
+        assert_eq!(first.open_time, 60);
+        assert!((first.open - 0.00003).abs() < 1e-12);
+        assert!((first.high - 0.00004).abs() < 1e-12);
+        assert!((first.close - 0.000035).abs() < 1e-12);
+        assert_eq!(
+            (first.buy_volume, first.sell_volume),
+            (2_000_000_000, 1_000_000_000)
+        );
+        assert_eq!((first.first_slot, first.last_slot), (10, 12));
+
+        let closed = builder.take_closed(120);
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].trades, 3);
+        assert!(!builder.push(
+            9,
+            &signatures[4],
+            0,
+            &trade(mint, 100, 30_000_000_000, true)
+        ));
+        assert_eq!(builder.dropped(), 1);
+        assert_eq!(builder.candles(&mint).len(), 1);
+    }
+}
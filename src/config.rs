@@ -0,0 +1,71 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    constants::{EVENT_AUTHORITY, MPL_TOKEN_METADATA},
+    pump_amm::PUMP_AMM_PROGRAM_ID,
+    PUMP_DOT_FUN_PROGRAM_ID,
+};
+
+/// Seed Anchor uses for a program's event authority PDA.
+const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Program addresses the SDK builds against. Defaults to the mainnet
+/// deployment; point it at another deployment, such as one on a local
+/// validator, with [`PumpConfig::with_program_id`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PumpConfig {
+    pub program_id: Pubkey,
+    pub event_authority: Pubkey,
+    pub metadata_program: Pubkey,
+    pub amm_program_id: Pubkey,
+}
+
+impl PumpConfig {
+    pub fn mainnet() -> Self {
+        Self {
+            program_id: PUMP_DOT_FUN_PROGRAM_ID,
+            event_authority: EVENT_AUTHORITY,
+            metadata_program: MPL_TOKEN_METADATA,
+            amm_program_id: PUMP_AMM_PROGRAM_ID,
+        }
+    }
+
+    /// Uses the pump.fun program deployed at `program_id`, deriving its
+    /// event authority.
+    pub fn with_program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = program_id;
+        self.event_authority = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &program_id).0;
+        self
+    }
+
+    pub fn with_metadata_program(mut self, metadata_program: Pubkey) -> Self {
+        self.metadata_program = metadata_program;
+        self
+    }
+
+    pub fn with_amm_program_id(mut self, amm_program_id: Pubkey) -> Self {
+        self.amm_program_id = amm_program_id;
+        self
+    }
+}
+
+impl Default for PumpConfig {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_authority_follows_program_id() {
+        let mainnet = PumpConfig::mainnet();
+        assert_eq!(mainnet.with_program_id(PUMP_DOT_FUN_PROGRAM_ID), mainnet);
+
+        let local = mainnet.with_program_id(Pubkey::new_unique());
+        assert_ne!(local.event_authority, EVENT_AUTHORITY);
+        assert_eq!(local.amm_program_id, PUMP_AMM_PROGRAM_ID);
+    }
+}
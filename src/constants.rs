@@ -5,6 +5,15 @@ pub static EVENT_AUTHORITY: Pubkey = pubkey!("Ce6TQqeHC9p8KetsN6JsjHK7UTZk7nasjj
 pub static SYSTEM_PROGRAM: Pubkey = pubkey!("11111111111111111111111111111111");
 pub static MPL_TOKEN_METADATA: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 pub static TOKEN_PROGRAM: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub static TOKEN_2022_PROGRAM: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
 pub static ASSOCIATED_TOKEN_PROGRAM: Pubkey =
     pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+pub static WSOL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
 pub static RENT_SYSVAR: Pubkey = pubkey!("SysvarRent111111111111111111111111111111111");
+/// Program whose accounts `create_v2` passes along for mayhem mode launches.
+pub static MAYHEM_PROGRAM: Pubkey = pubkey!("MAyhSmzXzV1pTf7LsNkrNwkWKTo4ougAJ1PPg47MD4e");
+
+/// Decimals used by every mint launched through pump.fun.
+pub const TOKEN_DECIMALS: u8 = 6;
+/// Real token reserves a fresh bonding curve starts with; used to derive curve progress.
+pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
 
+/// Errors raised by the SDK before anything reaches the chain. The anchor
+/// codes (6000 onwards) are client-side only and do not line up with the
+/// pump program's; use [`ErrorCode::program_error`] and
+/// [`ErrorCode::from_program_error`] to translate between the two.
 #[error_code]
 pub enum ErrorCode {
     #[msg("Invalid Slippage")]
@@ -12,4 +16,166 @@ pub enum ErrorCode {
     Overflow,
     #[msg("Bonding curve not found")]
     BondingCurveNotFound,
+    #[msg("The bonding curve has completed and liquidity migrated")]
+    BondingCurveComplete,
+    #[msg("Buy zero amount")]
+    BuyZeroAmount,
+    #[msg("Not enough tokens to buy")]
+    NotEnoughTokensToBuy,
+    #[msg("Sell zero amount")]
+    SellZeroAmount,
+    #[msg("Not enough tokens to sell")]
+    NotEnoughTokensToSell,
+    #[msg("Failed to open websocket subscription")]
+    SubscriptionFailed,
+    #[msg("Transaction not found")]
+    TransactionNotFound,
+    #[msg("Failed to fetch accounts from chain")]
+    AccountFetchFailed,
+    #[msg("The mint does not match the bonding curve")]
+    MintDoesNotMatchBondingCurve,
+    #[msg("Could not resolve the mint of the bonding curve")]
+    MintNotFound,
+    #[msg("Failed to read or write the mint index")]
+    MintIndexIo,
+    #[msg("Pump AMM pool not found")]
+    PoolNotFound,
+    #[msg("Address lookup table not found")]
+    LookupTableNotFound,
+    #[msg("Failed to compile versioned message")]
+    MessageCompileFailed,
+    #[msg("Failed to fetch a recent blockhash")]
+    BlockhashNotFound,
+    #[msg("Failed to sign transaction")]
+    SigningFailed,
+    #[msg("Failed to serialize transaction")]
+    SerializationError,
+    #[msg("Block engine rejected the bundle")]
+    BundleRejected,
+    #[msg("Nonce account not found")]
+    NonceAccountNotFound,
+    #[msg("Account is not an initialized nonce account")]
+    InvalidNonceAccount,
+    #[msg("Transaction does not advance the nonce account")]
+    NonceNotAdvanced,
+    #[msg("A required signature is missing")]
+    MissingSignature,
+    #[msg("Signature does not verify against the message")]
+    InvalidSignature,
+    #[msg("Invalid token or SOL amount")]
+    InvalidAmount,
+    #[msg("Token amount uses different decimals than the mint")]
+    DecimalsMismatch,
+    #[msg("Mint account not found")]
+    MintAccountNotFound,
+    #[msg("Mint is not owned by SPL Token or Token-2022")]
+    UnsupportedTokenProgram,
+    #[msg("Token metadata is missing a field or exceeds Metaplex limits")]
+    InvalidMetadata,
+    #[msg("Failed to upload token metadata")]
+    UploadFailed,
+    #[msg("Mint has no token metadata")]
+    MetadataNotFound,
+    #[msg("Failed to fetch the off-chain metadata JSON")]
+    MetadataJsonFetchFailed,
+    #[msg("Failed to build an SPL Token instruction")]
+    TokenInstructionFailed,
+    #[msg("Too many transactions for one bundle")]
+    BundleTooLarge,
+    #[msg("Create instruction names a different creator")]
+    CreatorMismatch,
+}
+
+/// Error codes of the pump program that the SDK's pre-flight checks mirror,
+/// with the numbers the program reports in a failed transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum PumpProgramError {
+    MintDoesNotMatchBondingCurve = 6004,
+    BondingCurveComplete = 6005,
+    BuyZeroAmount = 6020,
+    NotEnoughTokensToBuy = 6021,
+    SellZeroAmount = 6022,
+    NotEnoughTokensToSell = 6023,
+    Overflow = 6024,
+}
+
+impl PumpProgramError {
+    const ALL: [Self; 7] = [
+        Self::MintDoesNotMatchBondingCurve,
+        Self::BondingCurveComplete,
+        Self::BuyZeroAmount,
+        Self::NotEnoughTokensToBuy,
+        Self::SellZeroAmount,
+        Self::NotEnoughTokensToSell,
+        Self::Overflow,
+    ];
+
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+}
+
+impl ErrorCode {
+    /// The program error this SDK error anticipates, if any.
+    pub fn program_error(&self) -> Option<PumpProgramError> {
+        match self {
+            Self::MintDoesNotMatchBondingCurve => {
+                Some(PumpProgramError::MintDoesNotMatchBondingCurve)
+            }
+            Self::BondingCurveComplete => Some(PumpProgramError::BondingCurveComplete),
+            Self::BuyZeroAmount => Some(PumpProgramError::BuyZeroAmount),
+            Self::NotEnoughTokensToBuy => Some(PumpProgramError::NotEnoughTokensToBuy),
+            Self::SellZeroAmount => Some(PumpProgramError::SellZeroAmount),
+            Self::NotEnoughTokensToSell => Some(PumpProgramError::NotEnoughTokensToSell),
+            Self::Overflow => Some(PumpProgramError::Overflow),
+            _ => None,
+        }
+    }
+
+    /// The SDK error for a pump program error `code` taken from a failed
+    /// transaction, e.g. 6005 for [`ErrorCode::BondingCurveComplete`].
+    pub fn from_program_error(code: u32) -> Option<Self> {
+        Some(match PumpProgramError::from_code(code)? {
+            PumpProgramError::MintDoesNotMatchBondingCurve => Self::MintDoesNotMatchBondingCurve,
+            PumpProgramError::BondingCurveComplete => Self::BondingCurveComplete,
+            PumpProgramError::BuyZeroAmount => Self::BuyZeroAmount,
+            PumpProgramError::NotEnoughTokensToBuy => Self::NotEnoughTokensToBuy,
+            PumpProgramError::SellZeroAmount => Self::SellZeroAmount,
+            PumpProgramError::NotEnoughTokensToSell => Self::NotEnoughTokensToSell,
+            PumpProgramError::Overflow => Self::Overflow,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pre_flight_errors_map_to_program_codes() {
+        let expected = [
+            (ErrorCode::BondingCurveComplete, 6005),
+            (ErrorCode::BuyZeroAmount, 6020),
+            (ErrorCode::NotEnoughTokensToBuy, 6021),
+            (ErrorCode::SellZeroAmount, 6022),
+            (ErrorCode::NotEnoughTokensToSell, 6023),
+        ];
+        for (error, code) in expected {
+            assert_eq!(
+                error.program_error().map(PumpProgramError::code),
+                Some(code)
+            );
+            assert_eq!(
+                ErrorCode::from_program_error(code).and_then(|e| e.program_error()),
+                error.program_error()
+            );
+        }
+        assert!(ErrorCode::InvalidSlippage.program_error().is_none());
+        assert!(ErrorCode::from_program_error(6000).is_none());
+    }
 }
@@ -0,0 +1,211 @@
+pub mod create_event;
+pub use create_event::*;
+
+pub mod trade_event;
+pub use trade_event::*;
+
+use ::borsh::BorshDeserialize;
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use base64::{prelude::BASE64_STANDARD, Engine};
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::{
+    EncodedTransaction, UiInstruction, UiLoadedAddresses, UiMessage, UiTransactionEncoding,
+};
+
+use crate::{config::PumpConfig, errors::ErrorCode};
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+const PROGRAM_PREFIX: &str = "Program ";
+
+/// An Anchor event emitted by the pump.fun program.
+pub trait PumpEvent: BorshDeserialize {
+    const DISCRIMINATOR: [u8; 8];
+
+    /// Decodes an event payload that starts with the event discriminator.
+    fn decode(data: &[u8]) -> Option<Self> {
+        let payload = data.strip_prefix(Self::DISCRIMINATOR.as_slice())?;
+        Self::deserialize(&mut &payload[..]).ok()
+    }
+}
+
+/// Decodes every `T` found in `Program data:` log lines written while
+/// `config.program_id` was the executing program. The runtime's
+/// `Program <id> invoke [n]` and `success`/`failed` lines are followed to know
+/// which program logged each line, so data logged by any other program in
+/// the same transaction is ignored.
+pub fn parse_log_events<T: PumpEvent>(config: &PumpConfig, logs: &[String]) -> Vec<T> {
+    let program_id = config.program_id.to_string();
+    let mut invoked: Vec<&str> = vec![];
+    let mut events = vec![];
+
+    for log in logs {
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if invoked.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            if let Some(event) = BASE64_STANDARD
+                .decode(data)
+                .ok()
+                .and_then(|data| T::decode(&data))
+            {
+                events.push(event);
+            }
+            continue;
+        }
+
+        let Some(rest) = log.strip_prefix(PROGRAM_PREFIX) else {
+            continue;
+        };
+        let mut words = rest.split_whitespace();
+        let (Some(program), Some(status)) = (words.next(), words.next()) else {
+            continue;
+        };
+        match status {
+            "invoke" => invoked.push(program),
+            "success" | "failed:" => {
+                invoked.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Decodes a `T` from an event self-CPI: an instruction to `config.program_id`
+/// whose first account is `config.event_authority`, the only signer the
+/// program can produce for it. Any other instruction yields `None`.
+pub fn parse_cpi_event<T: PumpEvent>(
+    config: &PumpConfig,
+    program_id: &Pubkey,
+    accounts: &[Pubkey],
+    ix_data: &[u8],
+) -> Option<T> {
+    if *program_id != config.program_id || accounts.first() != Some(&config.event_authority) {
+        return None;
+    }
+    T::decode(ix_data.strip_prefix(EVENT_IX_TAG_LE)?)
+}
+
+/// Fetches a confirmed transaction and decodes every `T` the pump program
+/// emitted, either through program logs or through event-authority self-CPIs.
+pub fn fetch_transaction_events<T: PumpEvent>(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+) -> Result<Vec<T>, ErrorCode> {
+    let transaction = rpc_client
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(commitment),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .map_err(|_| ErrorCode::TransactionNotFound)?;
+
+    let Some(meta) = transaction.transaction.meta else {
+        return Ok(vec![]);
+    };
+
+    let logs: Option<Vec<String>> = meta.log_messages.into();
+    let mut events = parse_log_events(config, &logs.unwrap_or_default());
+    if !events.is_empty() {
+        return Ok(events);
+    }
+
+    // Inner instructions index into the static keys followed by the keys
+    // loaded from lookup tables, writable first.
+    let EncodedTransaction::Json(transaction) = transaction.transaction.transaction else {
+        return Ok(events);
+    };
+    let UiMessage::Raw(message) = transaction.message else {
+        return Ok(events);
+    };
+    let loaded: Option<UiLoadedAddresses> = meta.loaded_addresses.into();
+    let loaded = loaded.unwrap_or(UiLoadedAddresses {
+        writable: vec![],
+        readonly: vec![],
+    });
+    let account_keys: Vec<Pubkey> = message
+        .account_keys
+        .iter()
+        .chain(&loaded.writable)
+        .chain(&loaded.readonly)
+        .map(|key| key.parse().map_err(|_| ErrorCode::DeserializationError))
+        .collect::<Result<_, _>>()?;
+
+    let inner_instructions: Option<Vec<_>> = meta.inner_instructions.into();
+    for inner in inner_instructions.unwrap_or_default() {
+        for instruction in inner.instructions {
+            let UiInstruction::Compiled(compiled) = instruction else {
+                continue;
+            };
+            let Some(program_id) = account_keys.get(compiled.program_id_index as usize) else {
+                continue;
+            };
+            let accounts: Vec<Pubkey> = compiled
+                .accounts
+                .iter()
+                .filter_map(|index| account_keys.get(*index as usize).copied())
+                .collect();
+            let Ok(data) = bs58::decode(&compiled.data).into_vec() else {
+                continue;
+            };
+            events.extend(parse_cpi_event(config, program_id, &accounts, &data));
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::trade_event::tests::trade_event;
+    use ::borsh::BorshSerialize;
+
+    fn trade_data_log(mint: Pubkey) -> String {
+        let event = trade_event(mint, true);
+        let mut data = TradeEvent::DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+        format!("{PROGRAM_DATA_PREFIX}{}", BASE64_STANDARD.encode(data))
+    }
+
+    #[test]
+    fn test_events_logged_by_other_programs_are_ignored() {
+        let config = PumpConfig::default();
+        let pump = config.program_id.to_string();
+        let spoofer = Pubkey::new_unique().to_string();
+        let (spoofed, real) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let logs = [
+            format!("Program {spoofer} invoke [1]"),
+            trade_data_log(spoofed),
+            format!("Program {pump} invoke [2]"),
+            trade_data_log(real),
+            format!("Program {pump} consumed 5000 of 190000 compute units"),
+            format!("Program {pump} success"),
+            trade_data_log(spoofed),
+            format!("Program {spoofer} success"),
+            trade_data_log(spoofed),
+        ];
+        let events = parse_log_events::<TradeEvent>(&config, &logs);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].mint, real);
+
+        let mut data = EVENT_IX_TAG_LE.to_vec();
+        data.extend(
+            BASE64_STANDARD
+                .decode(&trade_data_log(real)[PROGRAM_DATA_PREFIX.len()..])
+                .unwrap(),
+        );
+        let cpi = |program: &Pubkey, authority: Pubkey| {
+            parse_cpi_event::<TradeEvent>(&config, program, &[authority], &data)
+        };
+        assert!(cpi(&config.program_id, config.event_authority).is_some());
+        assert!(cpi(&config.program_id, Pubkey::new_unique()).is_none());
+        assert!(cpi(&Pubkey::new_unique(), config.event_authority).is_none());
+    }
+}
@@ -0,0 +1,76 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::PumpEvent;
+use crate::states::price_from_reserves;
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct TradeEvent {
+    pub mint: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub is_buy: bool,
+    pub user: Pubkey,
+    pub timestamp: i64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub fee_recipient: Pubkey,
+    pub fee_basis_points: u64,
+    pub fee: u64,
+    pub creator: Pubkey,
+    pub creator_fee_basis_points: u64,
+    pub creator_fee: u64,
+}
+
+impl TradeEvent {
+    /// Spot price of one whole token in SOL right after the trade, from the
+    /// virtual reserves it reports.
+    pub fn price_in_sol(&self) -> f64 {
+        price_from_reserves(self.virtual_sol_reserves, self.virtual_token_reserves)
+    }
+}
+
+impl PumpEvent for TradeEvent {
+    const DISCRIMINATOR: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A 1 SOL trade of a million raw tokens against a fresh curve's
+    /// reserves, shared by tests across the crate.
+    pub(crate) fn trade_event(mint: Pubkey, is_buy: bool) -> TradeEvent {
+        TradeEvent {
+            mint,
+            sol_amount: 1_000_000_000,
+            token_amount: 1_000_000,
+            is_buy,
+            user: Pubkey::default(),
+            timestamp: 0,
+            virtual_sol_reserves: 30_000_000_000,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            real_sol_reserves: 0,
+            real_token_reserves: 0,
+            fee_recipient: Pubkey::default(),
+            fee_basis_points: 95,
+            fee: 0,
+            creator: Pubkey::default(),
+            creator_fee_basis_points: 5,
+            creator_fee: 0,
+        }
+    }
+
+    #[test]
+    fn test_price_matches_curve_reserves() {
+        let trade = trade_event(Pubkey::new_unique(), true);
+        assert!((trade.price_in_sol() - 30.0 / 1_073_000_000.0).abs() < 1e-15);
+        let emptied = TradeEvent {
+            virtual_token_reserves: 0,
+            ..trade
+        };
+        assert_eq!(emptied.price_in_sol(), 0.0);
+    }
+}
@@ -0,0 +1,276 @@
+use std::{collections::HashMap, thread};
+
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_request::MAX_MULTIPLE_ACCOUNTS,
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::{
+    config::PumpConfig,
+    constants::{TOKEN_2022_PROGRAM, TOKEN_PROGRAM},
+    errors::ErrorCode,
+    pda::{get_bonding_curve_pda, get_global_pda},
+    states::{BondingCurve, Global},
+};
+
+/// Narrows down [`list_bonding_curves`]; the default lists every curve.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BondingCurveFilter {
+    /// Only curves whose `complete` flag matches.
+    pub complete: Option<bool>,
+    /// Only curves launched by this creator.
+    pub creator: Option<Pubkey>,
+}
+
+/// `get_multiple_accounts` requests in flight at once.
+const MULTIPLE_ACCOUNTS_CONCURRENCY: usize = 8;
+
+/// Fetches `addresses` with `get_multiple_accounts`, splitting them into
+/// chunks of the RPC limit and running up to `MULTIPLE_ACCOUNTS_CONCURRENCY`
+/// chunks at a time. The result lines up with `addresses`; a failed chunk
+/// fails only its own entries.
+pub fn fetch_multiple_accounts(
+    rpc_client: &RpcClient,
+    addresses: &[Pubkey],
+) -> Vec<Result<Option<Account>, ErrorCode>> {
+    let mut accounts = Vec::with_capacity(addresses.len());
+    for batch in addresses.chunks(MAX_MULTIPLE_ACCOUNTS * MULTIPLE_ACCOUNTS_CONCURRENCY) {
+        thread::scope(|scope| {
+            let handles: Vec<_> = batch
+                .chunks(MAX_MULTIPLE_ACCOUNTS)
+                .map(|chunk| {
+                    (
+                        chunk.len(),
+                        scope.spawn(move || rpc_client.get_multiple_accounts(chunk).ok()),
+                    )
+                })
+                .collect();
+
+            accounts.extend(
+                handles
+                    .into_iter()
+                    .flat_map(|(len, handle)| match handle.join() {
+                        Ok(Some(accounts)) => accounts.into_iter().map(Ok).collect(),
+                        _ => vec![Err(ErrorCode::AccountFetchFailed); len],
+                    }),
+            );
+        });
+    }
+    accounts
+}
+
+pub fn fetch_global(rpc_client: &RpcClient, config: &PumpConfig) -> Result<Global, ErrorCode> {
+    let data = rpc_client
+        .get_account_data(&get_global_pda(config))
+        .map_err(|_| ErrorCode::GlobalNotFound)?;
+    Global::from_account_data(&data)
+}
+
+/// The token program that owns `mint`: SPL Token for classic launches,
+/// Token-2022 for mints created with `create_v2`. A missing mint is
+/// `MintAccountNotFound`; a failed request is `AccountFetchFailed`.
+pub fn fetch_token_program(rpc_client: &RpcClient, mint: &Pubkey) -> Result<Pubkey, ErrorCode> {
+    let account = rpc_client
+        .get_account_with_commitment(mint, rpc_client.commitment())
+        .map_err(|_| ErrorCode::AccountFetchFailed)?
+        .value
+        .ok_or(ErrorCode::MintAccountNotFound)?;
+    token_program_of(&account)
+}
+
+/// Raw balance of a token account under either token program. A missing
+/// account holds nothing and reads as 0; any other RPC failure is an error.
+pub fn fetch_token_balance(
+    rpc_client: &RpcClient,
+    token_account: &Pubkey,
+) -> Result<u64, ErrorCode> {
+    let account = rpc_client
+        .get_account_with_commitment(token_account, rpc_client.commitment())
+        .map_err(|_| ErrorCode::AccountFetchFailed)?
+        .value;
+    let Some(account) = account else {
+        return Ok(0);
+    };
+    // Both programs share the base layout: mint, owner, then the amount.
+    let amount = account
+        .data
+        .get(64..72)
+        .ok_or(ErrorCode::DeserializationError)?;
+    Ok(u64::from_le_bytes(amount.try_into().unwrap()))
+}
+
+/// Checks that a fetched mint account belongs to a supported token program
+/// and returns that program.
+pub fn token_program_of(mint_account: &Account) -> Result<Pubkey, ErrorCode> {
+    if mint_account.owner == TOKEN_PROGRAM || mint_account.owner == TOKEN_2022_PROGRAM {
+        Ok(mint_account.owner)
+    } else {
+        Err(ErrorCode::UnsupportedTokenProgram)
+    }
+}
+
+pub fn fetch_bonding_curve(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    mint: &Pubkey,
+) -> Result<BondingCurve, ErrorCode> {
+    let data = rpc_client
+        .get_account_data(&get_bonding_curve_pda(config, mint))
+        .map_err(|_| ErrorCode::BondingCurveNotFound)?;
+    BondingCurve::from_account_data(&data)
+}
+
+/// Fetches the bonding curves of many mints at once. Each mint maps to
+/// `Ok(None)` when it has no curve, or to its own error when the account
+/// could not be fetched or decoded; one bad account never aborts the batch.
+pub fn fetch_bonding_curves(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    mints: &[Pubkey],
+) -> HashMap<Pubkey, Result<Option<BondingCurve>, ErrorCode>> {
+    let bonding_curves: Vec<Pubkey> = mints
+        .iter()
+        .map(|mint| get_bonding_curve_pda(config, mint))
+        .collect();
+    let accounts = fetch_multiple_accounts(rpc_client, &bonding_curves);
+
+    mints
+        .iter()
+        .zip(accounts)
+        .map(|(mint, account)| {
+            let curve = account.and_then(|account| {
+                account
+                    .map(|account| BondingCurve::from_account_data(&account.data))
+                    .transpose()
+            });
+            (*mint, curve)
+        })
+        .collect()
+}
+
+/// Lists bonding curves owned by the program, keyed by curve address.
+/// RPC filters are AND-ed, so each supported account size is queried
+/// separately and the results merged. Accounts that fail to decode are skipped.
+/// Legacy curves carry no creator, so a `creator` filter leaves them out.
+pub fn list_bonding_curves(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    filter: BondingCurveFilter,
+) -> Result<HashMap<Pubkey, BondingCurve>, ErrorCode> {
+    let mut memcmps = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+        0,
+        BondingCurve::DISCRIMINATOR.to_vec(),
+    ))];
+    if let Some(complete) = filter.complete {
+        memcmps.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            BondingCurve::COMPLETE_OFFSET,
+            vec![complete as u8],
+        )));
+    }
+    if let Some(creator) = filter.creator {
+        memcmps.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            BondingCurve::CREATOR_OFFSET,
+            creator.as_ref(),
+        )));
+    }
+
+    let mut curves = HashMap::new();
+    let sizes = match filter.creator {
+        Some(_) => vec![BondingCurve::LEN, BondingCurve::EXTENDED_LEN],
+        None => vec![
+            BondingCurve::LEGACY_LEN,
+            BondingCurve::LEN,
+            BondingCurve::EXTENDED_LEN,
+        ],
+    };
+    for size in sizes {
+        let mut filters = vec![RpcFilterType::DataSize(size as u64)];
+        filters.extend(memcmps.iter().cloned());
+
+        let accounts = rpc_client
+            .get_program_accounts_with_config(
+                &config.program_id,
+                RpcProgramAccountsConfig {
+                    filters: Some(filters),
+                    account_config: RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        commitment: Some(rpc_client.commitment()),
+                        ..RpcAccountInfoConfig::default()
+                    },
+                    ..RpcProgramAccountsConfig::default()
+                },
+            )
+            .map_err(|_| ErrorCode::AccountFetchFailed)?;
+
+        curves.extend(accounts.into_iter().filter_map(|(address, account)| {
+            BondingCurve::from_account_data(&account.data)
+                .ok()
+                .map(|curve| (address, curve))
+        }));
+    }
+    Ok(curves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::borsh::BorshSerialize;
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use serde_json::json;
+    use solana_client::{rpc_client::Mocks, rpc_request::RpcRequest};
+
+    fn ui_account(data: &[u8]) -> serde_json::Value {
+        json!({
+            "lamports": 1,
+            "owner": Pubkey::default().to_string(),
+            "data": [BASE64_STANDARD.encode(data), "base64"],
+            "executable": false,
+            "rentEpoch": 0,
+            "space": data.len(),
+        })
+    }
+
+    #[test]
+    fn test_fetch_bonding_curves_keeps_per_account_errors() {
+        let curve = BondingCurve {
+            virtual_token_reserves: 1_073_000_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+            creator: Pubkey::new_unique(),
+        };
+        let mut data = BondingCurve::DISCRIMINATOR.to_vec();
+        curve.serialize(&mut data).unwrap();
+
+        let mut mocks = Mocks::default();
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            json!({
+                "context": { "slot": 1 },
+                "value": [ui_account(&data), ui_account(&[1, 2, 3]), null],
+            }),
+        );
+        let rpc_client = RpcClient::new_mock_with_mocks("succeeds", mocks);
+
+        let mints = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let curves = fetch_bonding_curves(&rpc_client, &PumpConfig::default(), &mints);
+
+        assert_eq!(curves.len(), 3);
+        assert!(matches!(&curves[&mints[0]], Ok(Some(c)) if c.creator == curve.creator));
+        assert!(matches!(
+            &curves[&mints[1]],
+            Err(ErrorCode::DeserializationError)
+        ));
+        assert!(matches!(&curves[&mints[2]], Ok(None)));
+    }
+}
@@ -0,0 +1,419 @@
+use std::{collections::HashMap, str::FromStr};
+
+use solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::UiTransactionEncoding;
+
+use crate::{
+    config::PumpConfig,
+    constants::TOKEN_PROGRAM,
+    errors::ErrorCode,
+    fetch::{fetch_bonding_curve, fetch_multiple_accounts, fetch_token_program},
+    pda::get_bonding_curve_pda,
+    pump_amm::pda::get_canonical_pool_pda,
+};
+
+/// Size of an SPL Token account; Token-2022 accounts with extensions are larger.
+const TOKEN_ACCOUNT_LEN: u64 = 165;
+/// Token accounts store the owner right after the mint, then the amount.
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+const SIGNATURE_PAGE_LIMIT: usize = 1000;
+/// Pages of history walked per wallet when looking for its first funding.
+const MAX_FUNDING_PAGES: usize = 3;
+
+/// Tunes [`analyze_holders`]; the default reports the top 10 holders and
+/// traces who funded them.
+#[derive(Debug, Clone, Copy)]
+pub struct HolderAnalysisOptions {
+    /// Holders listed in the report and checked for shared funders.
+    pub top: usize,
+    /// Look up the wallet that paid for each top holder's first transaction.
+    /// Costs a few RPC calls per holder.
+    pub trace_funding: bool,
+}
+
+impl Default for HolderAnalysisOptions {
+    fn default() -> Self {
+        Self {
+            top: 10,
+            trace_funding: true,
+        }
+    }
+}
+
+/// Owners of the token accounts that hold a mint's liquidity rather than a
+/// position: the bonding curve and, once migrated, the canonical AMM pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiquidityOwners {
+    pub bonding_curve: Pubkey,
+    pub amm_pool: Pubkey,
+}
+
+impl LiquidityOwners {
+    pub fn new(config: &PumpConfig, mint: &Pubkey) -> Self {
+        Self {
+            bonding_curve: get_bonding_curve_pda(config, mint),
+            amm_pool: get_canonical_pool_pda(config, mint),
+        }
+    }
+}
+
+/// A wallet's combined balance across its token accounts for the mint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holder {
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// Fraction of the total supply, between 0 and 1.
+    pub share: f64,
+}
+
+/// Top holders whose first transaction was paid for by the same wallet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundingCluster {
+    pub funder: Pubkey,
+    pub holders: Vec<Pubkey>,
+    pub amount: u64,
+    pub share: f64,
+    /// The curve's creator funded the cluster or is part of it.
+    pub involves_creator: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HolderReport {
+    pub mint: Pubkey,
+    pub total_supply: u64,
+    /// Wallets holding a non-zero balance. `None` when the RPC refused the
+    /// token account scan and only the largest accounts could be read.
+    pub holder_count: Option<usize>,
+    /// Tokens still held by the bonding curve, which is left out of
+    /// every other figure.
+    pub bonding_curve_amount: u64,
+    /// Tokens in the AMM pool's base vault after migration, likewise left
+    /// out of every other figure.
+    pub amm_pool_amount: u64,
+    pub top_holders: Vec<Holder>,
+    /// Combined share of `top_holders`.
+    pub top_holders_share: f64,
+    pub creator: Pubkey,
+    pub creator_amount: u64,
+    pub creator_share: f64,
+    /// Top holders sharing a funder, and any funded by the creator.
+    pub clusters: Vec<FundingCluster>,
+}
+
+fn share_of(amount: u64, total_supply: u64) -> f64 {
+    if total_supply == 0 {
+        return 0.0;
+    }
+    amount as f64 / total_supply as f64
+}
+
+/// Builds the report from `(owner, amount)` token account balances, before
+/// funding is traced. Balances owned by the bonding curve or the AMM pool
+/// count towards `bonding_curve_amount` or `amm_pool_amount` only.
+pub fn summarize_holders(
+    mint: &Pubkey,
+    liquidity: &LiquidityOwners,
+    creator: &Pubkey,
+    total_supply: u64,
+    balances: &[(Pubkey, u64)],
+    top: usize,
+    complete: bool,
+) -> HolderReport {
+    let mut bonding_curve_amount = 0;
+    let mut amm_pool_amount = 0;
+    let mut by_owner: HashMap<Pubkey, u64> = HashMap::new();
+    for (owner, amount) in balances {
+        if *owner == liquidity.bonding_curve {
+            bonding_curve_amount += amount;
+        } else if *owner == liquidity.amm_pool {
+            amm_pool_amount += amount;
+        } else if *amount > 0 {
+            *by_owner.entry(*owner).or_default() += amount;
+        }
+    }
+
+    let creator_amount = by_owner.get(creator).copied().unwrap_or(0);
+    let mut holders: Vec<Holder> = by_owner
+        .iter()
+        .map(|(owner, amount)| Holder {
+            owner: *owner,
+            amount: *amount,
+            share: share_of(*amount, total_supply),
+        })
+        .collect();
+    holders.sort_by(|a, b| b.amount.cmp(&a.amount).then(a.owner.cmp(&b.owner)));
+    holders.truncate(top);
+
+    HolderReport {
+        mint: *mint,
+        total_supply,
+        holder_count: complete.then_some(by_owner.len()),
+        bonding_curve_amount,
+        amm_pool_amount,
+        top_holders_share: share_of(holders.iter().map(|h| h.amount).sum(), total_supply),
+        top_holders: holders,
+        creator: *creator,
+        creator_amount,
+        creator_share: share_of(creator_amount, total_supply),
+        clusters: vec![],
+    }
+}
+
+/// Groups the report's top holders by the wallet that funded them, keeping
+/// groups of two or more and any holder funded by the creator.
+pub fn cluster_by_funder(
+    report: &HolderReport,
+    funders: &HashMap<Pubkey, Pubkey>,
+) -> Vec<FundingCluster> {
+    let mut clusters: Vec<FundingCluster> = vec![];
+    for holder in &report.top_holders {
+        let Some(funder) = funders.get(&holder.owner) else {
+            continue;
+        };
+        match clusters
+            .iter_mut()
+            .find(|cluster| cluster.funder == *funder)
+        {
+            Some(cluster) => {
+                cluster.holders.push(holder.owner);
+                cluster.amount += holder.amount;
+            }
+            None => clusters.push(FundingCluster {
+                funder: *funder,
+                holders: vec![holder.owner],
+                amount: holder.amount,
+                share: 0.0,
+                involves_creator: false,
+            }),
+        }
+    }
+
+    clusters.retain_mut(|cluster| {
+        cluster.share = share_of(cluster.amount, report.total_supply);
+        cluster.involves_creator =
+            cluster.funder == report.creator || cluster.holders.contains(&report.creator);
+        cluster.holders.len() > 1 || cluster.funder == report.creator
+    });
+    clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.amount));
+    clusters
+}
+
+/// Reports who holds `mint`: concentration among the top holders, the
+/// creator's stake, the holder count and top holders funded by the same
+/// wallet. Scans the mint's token accounts, falling back to
+/// `get_token_largest_accounts` on RPCs that refuse the scan.
+pub fn analyze_holders(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    mint: &Pubkey,
+    options: HolderAnalysisOptions,
+) -> Result<HolderReport, ErrorCode> {
+    let curve = fetch_bonding_curve(rpc_client, config, mint)?;
+    let token_program = fetch_token_program(rpc_client, mint)?;
+
+    let (balances, complete) = match scan_token_accounts(rpc_client, mint, &token_program) {
+        Ok(balances) => (balances, true),
+        Err(_) => (largest_token_accounts(rpc_client, mint)?, false),
+    };
+
+    let mut report = summarize_holders(
+        mint,
+        &LiquidityOwners::new(config, mint),
+        &curve.creator,
+        curve.token_total_supply,
+        &balances,
+        options.top,
+        complete,
+    );
+
+    if options.trace_funding {
+        let funders: HashMap<Pubkey, Pubkey> = report
+            .top_holders
+            .iter()
+            .filter_map(|holder| {
+                find_funder(rpc_client, &holder.owner).map(|funder| (holder.owner, funder))
+            })
+            .collect();
+        report.clusters = cluster_by_funder(&report, &funders);
+    }
+    Ok(report)
+}
+
+/// Owner and amount of every token account of `mint`.
+fn scan_token_accounts(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Vec<(Pubkey, u64)>, ErrorCode> {
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        0,
+        mint.as_ref(),
+    ))];
+    if *token_program == TOKEN_PROGRAM {
+        filters.push(RpcFilterType::DataSize(TOKEN_ACCOUNT_LEN));
+    }
+
+    let accounts = rpc_client
+        .get_program_accounts_with_config(
+            token_program,
+            RpcProgramAccountsConfig {
+                filters: Some(filters),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    data_slice: Some(UiDataSliceConfig {
+                        offset: TOKEN_ACCOUNT_OWNER_OFFSET,
+                        length: 40,
+                    }),
+                    commitment: Some(rpc_client.commitment()),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            },
+        )
+        .map_err(|_| ErrorCode::AccountFetchFailed)?;
+
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(_, account)| owner_and_amount(&account.data))
+        .collect())
+}
+
+/// The largest token accounts of `mint` with their owners resolved.
+fn largest_token_accounts(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+) -> Result<Vec<(Pubkey, u64)>, ErrorCode> {
+    let addresses: Vec<Pubkey> = rpc_client
+        .get_token_largest_accounts(mint)
+        .map_err(|_| ErrorCode::AccountFetchFailed)?
+        .iter()
+        .filter_map(|balance| Pubkey::from_str(&balance.address).ok())
+        .collect();
+
+    fetch_multiple_accounts(rpc_client, &addresses)
+        .into_iter()
+        .filter_map(|account| account.transpose())
+        .map(|account| {
+            let account = account?;
+            account
+                .data
+                .get(TOKEN_ACCOUNT_OWNER_OFFSET..)
+                .and_then(owner_and_amount)
+                .ok_or(ErrorCode::DeserializationError)
+        })
+        .collect()
+}
+
+/// Reads the owner and amount from token account data starting at the owner.
+fn owner_and_amount(data: &[u8]) -> Option<(Pubkey, u64)> {
+    let owner = Pubkey::try_from(data.get(..32)?).ok()?;
+    let amount = u64::from_le_bytes(data.get(32..40)?.try_into().ok()?);
+    Some((owner, amount))
+}
+
+/// The fee payer of `wallet`'s oldest transaction, if someone other than
+/// the wallet paid for it. `None` when the history is longer than we walk.
+fn find_funder(rpc_client: &RpcClient, wallet: &Pubkey) -> Option<Pubkey> {
+    let mut before = None;
+    let mut oldest = None;
+    for _ in 0..MAX_FUNDING_PAGES {
+        let page = rpc_client
+            .get_signatures_for_address_with_config(
+                wallet,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    limit: Some(SIGNATURE_PAGE_LIMIT),
+                    ..GetConfirmedSignaturesForAddress2Config::default()
+                },
+            )
+            .ok()?;
+        let signature = Signature::from_str(&page.last()?.signature).ok()?;
+        if page.len() < SIGNATURE_PAGE_LIMIT {
+            oldest = Some(signature);
+            break;
+        }
+        before = Some(signature);
+    }
+
+    let transaction = rpc_client
+        .get_transaction_with_config(
+            &oldest?,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(rpc_client.commitment()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .ok()?
+        .transaction
+        .transaction
+        .decode()?;
+    let payer = *transaction.message.static_account_keys().first()?;
+    (payer != *wallet).then_some(payer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_excludes_curve_and_clusters_shared_funders() {
+        let mint = Pubkey::new_unique();
+        let liquidity = LiquidityOwners {
+            bonding_curve: Pubkey::new_unique(),
+            amm_pool: Pubkey::new_unique(),
+        };
+        let creator = Pubkey::new_unique();
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let supply = 1_000_000;
+        let balances = [
+            (liquidity.bonding_curve, 400_000),
+            (liquidity.amm_pool, 200_000),
+            (a, 100_000),
+            (b, 50_000),
+            (b, 30_000), // second token account of the same wallet
+            (c, 20_000),
+            (creator, 40_000),
+            (Pubkey::new_unique(), 0),
+        ];
+
+        let report = summarize_holders(&mint, &liquidity, &creator, supply, &balances, 3, true);
+
+        assert_eq!(report.bonding_curve_amount, 400_000);
+        assert_eq!(report.amm_pool_amount, 200_000);
+        assert_eq!(report.holder_count, Some(4));
+        assert_eq!(
+            report
+                .top_holders
+                .iter()
+                .map(|h| (h.owner, h.amount))
+                .collect::<Vec<_>>(),
+            vec![(a, 100_000), (b, 80_000), (creator, 40_000)]
+        );
+        assert!((report.top_holders_share - 0.22).abs() < 1e-9);
+        assert_eq!(report.creator_amount, 40_000);
+
+        let funder = Pubkey::new_unique();
+        let funders = HashMap::from([(a, funder), (b, funder), (creator, Pubkey::new_unique())]);
+        let clusters = cluster_by_funder(&report, &funders);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].holders, vec![a, b]);
+        assert_eq!(clusters[0].amount, 180_000);
+        assert!(!clusters[0].involves_creator);
+
+        let funders = HashMap::from([(a, creator)]);
+        let clusters = cluster_by_funder(&report, &funders);
+        assert_eq!(clusters.len(), 1);
+        assert!(clusters[0].involves_creator);
+    }
+}
@@ -1,4 +1,3 @@
-use ::borsh::BorshDeserialize;
 use anchor_lang::prelude::*;
 use core::result::Result;
 use solana_client::rpc_client::RpcClient;
@@ -7,39 +6,129 @@ use solana_sdk::{
     pubkey::Pubkey,
 };
 use spl_associated_token_account::{
-    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
 };
 
 use crate::{
-    constants::{EVENT_AUTHORITY, SYSTEM_PROGRAM, TOKEN_PROGRAM},
+    amount::TokenAmount,
+    config::PumpConfig,
+    constants::SYSTEM_PROGRAM,
     errors::ErrorCode,
+    fetch::{fetch_bonding_curve, fetch_global, fetch_token_program},
     pda::{
         get_associated_bonding_curve, get_bonding_curve_pda, get_creator_vault_pda, get_global_pda,
     },
-    states::{BondingCurve, Global},
-    PUMP_DOT_FUN_PROGRAM_ID,
 };
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Buy {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub amount: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub max_sol_cost: u64,
     pub slippage: i64,
 }
 
+impl Buy {
+    /// A buy of `amount` pump tokens paying at most `max_sol_cost` lamports.
+    pub fn new(amount: TokenAmount, max_sol_cost: u64, slippage: i64) -> Result<Self, ErrorCode> {
+        Ok(Self {
+            amount: amount.pump_raw()?,
+            max_sol_cost,
+            slippage,
+        })
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BuyArgs {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub amount: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub max_sol_cost: u64,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BuyAccounts {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub mint: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub user: Pubkey,
 }
 
+/// Raises `max_sol_cost` by `slippage` percent.
+pub fn max_sol_cost_with_slippage(max_sol_cost: u64, slippage: i64) -> Result<u64, ErrorCode> {
+    if slippage < 0 {
+        return Err(ErrorCode::InvalidSlippage);
+    }
+
+    let max_cost = max_sol_cost as u128;
+    let slippage = slippage as u128;
+
+    let slippage_amount = max_cost
+        .checked_mul(slippage)
+        .and_then(|v| v.checked_mul(10)) // for basis points scaling
+        .and_then(|v| v.checked_div(1000))
+        .ok_or(ErrorCode::Overflow)?; // still u128
+
+    max_cost
+        .checked_add(slippage_amount)
+        .ok_or(ErrorCode::Overflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::Overflow)
+}
+
+/// Builds the buy instruction from already known state, without touching
+/// the chain. `creator` is the curve's creator, which owns the creator vault,
+/// and `token_program` is the program that owns the mint.
+pub fn build_buy_ix(
+    config: &PumpConfig,
+    accounts: &BuyAccounts,
+    args: &BuyArgs,
+    fee_recipient: &Pubkey,
+    creator: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let discriminator = [102, 6, 61, 18, 1, 218, 235, 234];
+    let mut data = discriminator.to_vec();
+
+    let bonding_curve = get_bonding_curve_pda(config, &accounts.mint);
+    let associated_bonding_curve =
+        get_associated_bonding_curve(config, &accounts.mint, token_program);
+    let global_pda = get_global_pda(config);
+    let associated_user_token_account =
+        get_associated_token_address_with_program_id(&accounts.user, &accounts.mint, token_program);
+    let creator_vault = get_creator_vault_pda(config, creator);
+
+    let accounts_metas = vec![
+        AccountMeta::new_readonly(global_pda, false),
+        AccountMeta::new(*fee_recipient, false),
+        AccountMeta::new_readonly(accounts.mint, false),
+        AccountMeta::new(bonding_curve, false),
+        AccountMeta::new(associated_bonding_curve, false),
+        AccountMeta::new(associated_user_token_account, false),
+        AccountMeta::new(accounts.user, true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
+        AccountMeta::new_readonly(*token_program, false),
+        AccountMeta::new(creator_vault, false),
+        AccountMeta::new_readonly(config.event_authority, false),
+        AccountMeta::new_readonly(config.program_id, false),
+    ];
+
+    data.extend(args.try_to_vec().unwrap());
+
+    Instruction {
+        program_id: config.program_id,
+        accounts: accounts_metas,
+        data,
+    }
+}
+
 pub fn buy_ix(
     rpc_client: &RpcClient,
-    program_id: &Pubkey,
+    config: &PumpConfig,
     accounts: BuyAccounts,
     args: Buy,
 ) -> Result<Vec<Instruction>, ErrorCode> {
@@ -47,33 +136,20 @@ pub fn buy_ix(
         return Err(ErrorCode::InvalidSlippage);
     }
 
-    let discriminator = [102, 6, 61, 18, 1, 218, 235, 234];
-    let mut data = discriminator.to_vec();
-
-    let bonding_curve = get_bonding_curve_pda(&accounts.mint);
-    let associated_bonding_curve = get_associated_bonding_curve(&accounts.mint);
-    let global_pda = get_global_pda();
-    let associated_user_token_account =
-        get_associated_token_address(&accounts.user, &accounts.mint);
-
-    let global_account_data = rpc_client
-        .get_account_data(&global_pda)
-        .map_err(|_| ErrorCode::GlobalNotFound)?;
+    let token_program = fetch_token_program(rpc_client, &accounts.mint)?;
+    let associated_user_token_account = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.mint,
+        &token_program,
+    );
 
-    let global: Global = Global::deserialize(&mut &global_account_data[8..])
-        .map_err(|_| ErrorCode::DeserializationError)?;
+    let global = fetch_global(rpc_client, config)?;
 
     let fee_recipient = global.fee_recipient;
 
-    let bonding_curve_data = rpc_client
-        .get_account_data(&bonding_curve)
-        .map_err(|_| ErrorCode::BondingCurveNotFound)?;
+    let bonding_curve_account_data = fetch_bonding_curve(rpc_client, config, &accounts.mint)?;
 
-    let bonding_curve_account_data: BondingCurve =
-        BondingCurve::deserialize(&mut &bonding_curve_data[8..])
-            .map_err(|_| ErrorCode::DeserializationError)?;
-
-    let creator_vault = get_creator_vault_pda(&bonding_curve_account_data.creator);
+    bonding_curve_account_data.validate_buy(args.amount)?;
 
     // CHECK IF ATA exists
 
@@ -86,55 +162,27 @@ pub fn buy_ix(
             &accounts.user,
             &accounts.user,
             &accounts.mint,
-            &TOKEN_PROGRAM,
+            &token_program,
         );
         instructions.push(create_ata_ix);
     }
 
-    let accounts_metas = vec![
-        AccountMeta::new_readonly(global_pda, false),
-        AccountMeta::new(fee_recipient, false),
-        AccountMeta::new_readonly(accounts.mint, false),
-        AccountMeta::new(bonding_curve, false),
-        AccountMeta::new(associated_bonding_curve, false),
-        AccountMeta::new(associated_user_token_account, false),
-        AccountMeta::new(accounts.user, true),
-        AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
-        AccountMeta::new_readonly(TOKEN_PROGRAM, false),
-        AccountMeta::new(creator_vault, false),
-        AccountMeta::new_readonly(EVENT_AUTHORITY, false),
-        AccountMeta::new_readonly(PUMP_DOT_FUN_PROGRAM_ID, false),
-    ];
-
     // Calculate the sol_amount_to_pay based on slippage
-
-    let max_cost = args.max_sol_cost as u128;
-    let slippage = args.slippage as u128;
-
-    let slippage_amount = max_cost
-        .checked_mul(slippage)
-        .and_then(|v| v.checked_mul(10)) // for basis points scaling
-        .and_then(|v| v.checked_div(1000))
-        .ok_or(ErrorCode::Overflow)?; // still u128
-
-    let new_sol_amount: u64 = max_cost
-        .checked_add(slippage_amount)
-        .ok_or(ErrorCode::Overflow)?
-        .try_into()
-        .map_err(|_| ErrorCode::Overflow)?;
+    let new_sol_amount = max_sol_cost_with_slippage(args.max_sol_cost, args.slippage)?;
 
     let buy_args = BuyArgs {
         amount: args.amount,
         max_sol_cost: new_sol_amount,
     };
 
-    data.extend(buy_args.try_to_vec().unwrap());
-
-    let buy_instruction = Instruction {
-        program_id: *program_id,
-        accounts: accounts_metas,
-        data,
-    };
+    let buy_instruction = build_buy_ix(
+        config,
+        &accounts,
+        &buy_args,
+        &fee_recipient,
+        &bonding_curve_account_data.creator,
+        &token_program,
+    );
 
     instructions.push(buy_instruction);
 
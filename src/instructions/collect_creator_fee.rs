@@ -0,0 +1,32 @@
+use crate::{config::PumpConfig, constants::SYSTEM_PROGRAM, pda::get_creator_vault_pda};
+use anchor_lang::prelude::*;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CollectCreatorFeeAccounts {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
+    pub creator: Pubkey,
+}
+
+/// Moves the creator fees accrued in the creator's vault to the creator.
+pub fn collect_creator_fee_ix(
+    config: &PumpConfig,
+    accounts: CollectCreatorFeeAccounts,
+) -> Instruction {
+    let discriminator = [20, 22, 86, 123, 198, 28, 219, 132];
+    let creator_vault = get_creator_vault_pda(config, &accounts.creator);
+
+    let accounts = vec![
+        AccountMeta::new(accounts.creator, true),
+        AccountMeta::new(creator_vault, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
+        AccountMeta::new_readonly(config.event_authority, false),
+        AccountMeta::new_readonly(config.program_id, false),
+    ];
+
+    Instruction {
+        program_id: config.program_id,
+        accounts,
+        data: discriminator.to_vec(),
+    }
+}
@@ -1,40 +1,50 @@
 use crate::{
+    config::PumpConfig,
     constants::{
-        ASSOCIATED_TOKEN_PROGRAM, EVENT_AUTHORITY, MPL_TOKEN_METADATA, RENT_SYSVAR, SYSTEM_PROGRAM,
+        ASSOCIATED_TOKEN_PROGRAM, MAYHEM_PROGRAM, RENT_SYSVAR, SYSTEM_PROGRAM, TOKEN_2022_PROGRAM,
         TOKEN_PROGRAM,
     },
     pda::{
-        get_associated_bonding_curve, get_bonding_curve_pda, get_global_pda, get_metadata_pda,
-        get_mint_authority_pda,
+        get_associated_bonding_curve, get_bonding_curve_pda, get_global_pda,
+        get_mayhem_global_params_pda, get_mayhem_sol_vault_pda, get_mayhem_state_pda,
+        get_mayhem_token_vault, get_metadata_pda, get_mint_authority_pda,
     },
-    PUMP_DOT_FUN_PROGRAM_ID,
 };
 use anchor_lang::prelude::*;
 use solana_sdk::instruction::{AccountMeta, Instruction};
 
 #[derive(AnchorDeserialize, AnchorSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CreateArgs {
     pub name: String,
     pub symbol: String,
     pub uri: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub creator: Pubkey,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CreateAccounts {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub mint: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub user: Pubkey,
 }
 
-pub fn create_ix(program_id: &Pubkey, accounts: CreateAccounts, args: CreateArgs) -> Instruction {
-    let discriminator = [24, 30, 200, 40, 5, 28, 7, 119];
-    let mut data = discriminator.to_vec();
+const CREATE_DISCRIMINATOR: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
+const CREATE_V2_DISCRIMINATOR: [u8; 8] = [214, 144, 76, 236, 95, 139, 49, 180];
+
+/// Creates an SPL Token mint with Metaplex metadata.
+pub fn create_ix(config: &PumpConfig, accounts: CreateAccounts, args: CreateArgs) -> Instruction {
+    let mut data = CREATE_DISCRIMINATOR.to_vec();
     data.extend(args.try_to_vec().unwrap());
 
-    let bonding_curve = get_bonding_curve_pda(&accounts.mint);
-    let associated_bonding_curve = get_associated_bonding_curve(&accounts.mint);
-    let metadata_pda = get_metadata_pda(&accounts.mint);
-    let global_pda = get_global_pda();
-    let mint_authority_pda = get_mint_authority_pda();
+    let bonding_curve = get_bonding_curve_pda(config, &accounts.mint);
+    let associated_bonding_curve =
+        get_associated_bonding_curve(config, &accounts.mint, &TOKEN_PROGRAM);
+    let metadata_pda = get_metadata_pda(config, &accounts.mint);
+    let global_pda = get_global_pda(config);
+    let mint_authority_pda = get_mint_authority_pda(config);
 
     let accounts = vec![
         AccountMeta::new(accounts.mint, true),
@@ -42,24 +52,95 @@ pub fn create_ix(program_id: &Pubkey, accounts: CreateAccounts, args: CreateArgs
         AccountMeta::new(bonding_curve, false),
         AccountMeta::new(associated_bonding_curve, false),
         AccountMeta::new(global_pda, false),
-        AccountMeta::new_readonly(MPL_TOKEN_METADATA, false),
+        AccountMeta::new_readonly(config.metadata_program, false),
         AccountMeta::new(metadata_pda, false),
         AccountMeta::new(accounts.user, true),
         AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
         AccountMeta::new_readonly(TOKEN_PROGRAM, false),
         AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM, false),
         AccountMeta::new_readonly(RENT_SYSVAR, false),
-        AccountMeta::new_readonly(EVENT_AUTHORITY, false),
-        AccountMeta::new_readonly(PUMP_DOT_FUN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(config.event_authority, false),
+        AccountMeta::new_readonly(config.program_id, false),
+    ];
+
+    Instruction {
+        program_id: config.program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Creates a Token-2022 mint. Name, symbol and uri are stored in the mint's
+/// own metadata extension, so no Metaplex accounts are involved. The coin
+/// is launched outside mayhem mode.
+pub fn create_v2_ix(
+    config: &PumpConfig,
+    accounts: CreateAccounts,
+    args: CreateArgs,
+) -> Instruction {
+    let mut data = CREATE_V2_DISCRIMINATOR.to_vec();
+    data.extend(args.try_to_vec().unwrap());
+    // is_mayhem_mode
+    data.push(false as u8);
+
+    let mint = accounts.mint;
+    let bonding_curve = get_bonding_curve_pda(config, &mint);
+    let associated_bonding_curve = get_associated_bonding_curve(config, &mint, &TOKEN_2022_PROGRAM);
+    let global_pda = get_global_pda(config);
+    let mint_authority_pda = get_mint_authority_pda(config);
+
+    let accounts = vec![
+        AccountMeta::new(accounts.mint, true),
+        AccountMeta::new_readonly(mint_authority_pda, false),
+        AccountMeta::new(bonding_curve, false),
+        AccountMeta::new(associated_bonding_curve, false),
+        AccountMeta::new_readonly(global_pda, false),
+        AccountMeta::new(accounts.user, true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM, false),
+        AccountMeta::new(MAYHEM_PROGRAM, false),
+        AccountMeta::new_readonly(get_mayhem_global_params_pda(), false),
+        AccountMeta::new(get_mayhem_sol_vault_pda(), false),
+        AccountMeta::new(get_mayhem_state_pda(&mint), false),
+        AccountMeta::new(get_mayhem_token_vault(&mint), false),
+        AccountMeta::new_readonly(config.event_authority, false),
+        AccountMeta::new_readonly(config.program_id, false),
     ];
 
     Instruction {
-        program_id: *program_id,
+        program_id: config.program_id,
         accounts,
         data,
     }
 }
 
+/// The token program of the mint a create instruction sets up: Token-2022
+/// for [`create_v2_ix`], SPL Token otherwise.
+pub fn create_token_program(create_ix: &Instruction) -> Pubkey {
+    if create_ix.data.starts_with(&CREATE_V2_DISCRIMINATOR) {
+        TOKEN_2022_PROGRAM
+    } else {
+        TOKEN_PROGRAM
+    }
+}
+
+/// The creator recorded in a `create` or `create_v2` instruction's
+/// arguments, or `None` if the data is not a create.
+pub fn create_creator(create_ix: &Instruction) -> Option<Pubkey> {
+    let args = create_ix
+        .data
+        .strip_prefix(CREATE_DISCRIMINATOR.as_slice())
+        .or_else(|| {
+            create_ix
+                .data
+                .strip_prefix(CREATE_V2_DISCRIMINATOR.as_slice())
+        })?;
+    CreateArgs::deserialize(&mut &args[..])
+        .ok()
+        .map(|args| args.creator)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,14 +163,54 @@ mod tests {
         };
 
         let program_id = Pubkey::new_unique(); // Dummy program ID for testing
+        let config = PumpConfig::default().with_program_id(program_id);
 
-        let instruction = create_ix(&program_id, accounts, args);
+        let instruction = create_ix(&config, accounts, args);
 
         // Assertions
         assert_eq!(instruction.program_id, program_id);
-        // Add more assertions here to check accounts and data
-        // For example:
-        // assert_eq!(instruction.accounts.len(), 13); // Check the number of accounts
-        // assert!(instruction.data.starts_with(&[24, 30, 200, 40, 5, 28, 7, 119])); // Check discriminator
+        assert_eq!(
+            instruction.accounts[2].pubkey,
+            get_bonding_curve_pda(&config, &instruction.accounts[0].pubkey)
+        );
+        assert_eq!(instruction.accounts.len(), 14);
+        assert!(instruction
+            .data
+            .starts_with(&[24, 30, 200, 40, 5, 28, 7, 119]));
+    }
+
+    #[test]
+    fn test_create_v2_ix_uses_token_2022() {
+        let config = PumpConfig::default();
+        let mint = Pubkey::new_unique();
+        let accounts = CreateAccounts {
+            mint,
+            user: Pubkey::new_unique(),
+        };
+        let args = CreateArgs {
+            name: "Test Token".to_string(),
+            symbol: "TEST".to_string(),
+            uri: "http://test.uri".to_string(),
+            creator: Pubkey::new_unique(),
+        };
+
+        let instruction = create_v2_ix(&config, accounts, args);
+
+        assert!(instruction.data.starts_with(&CREATE_V2_DISCRIMINATOR));
+        assert_eq!(instruction.data.last(), Some(&0));
+        assert_eq!(instruction.accounts.len(), 16);
+        assert_eq!(create_token_program(&instruction), TOKEN_2022_PROGRAM);
+        assert_eq!(instruction.accounts[7].pubkey, TOKEN_2022_PROGRAM);
+        assert_eq!(
+            instruction.accounts[3].pubkey,
+            get_associated_bonding_curve(&config, &mint, &TOKEN_2022_PROGRAM)
+        );
+        assert_eq!(instruction.accounts[9].pubkey, MAYHEM_PROGRAM);
+        assert_eq!(instruction.accounts[12].pubkey, get_mayhem_state_pda(&mint));
+        assert_eq!(instruction.accounts[14].pubkey, config.event_authority);
+        assert!(instruction
+            .accounts
+            .iter()
+            .all(|meta| meta.pubkey != config.metadata_program));
     }
 }
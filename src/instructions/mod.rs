@@ -6,3 +6,6 @@ pub use buy::*;
 
 pub mod sell;
 pub use sell::*;
+
+pub mod collect_creator_fee;
+pub use collect_creator_fee::*;
@@ -1,4 +1,3 @@
-use ::borsh::BorshDeserialize;
 use anchor_lang::prelude::*;
 use core::result::Result;
 use solana_client::rpc_client::RpcClient;
@@ -6,38 +5,81 @@ use solana_sdk::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
 };
-use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 
 use crate::{
-    constants::{EVENT_AUTHORITY, SYSTEM_PROGRAM, TOKEN_PROGRAM},
+    amount::TokenAmount,
+    config::PumpConfig,
+    constants::SYSTEM_PROGRAM,
     errors::ErrorCode,
+    fetch::{fetch_bonding_curve, fetch_global, fetch_token_balance, fetch_token_program},
     pda::{
         get_associated_bonding_curve, get_bonding_curve_pda, get_creator_vault_pda, get_global_pda,
     },
-    states::{BondingCurve, Global},
-    PUMP_DOT_FUN_PROGRAM_ID,
 };
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SellArgs {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub amount: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub min_sol_output: u64,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sell {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub amount: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub min_sol_output: u64,
     pub slippage: i64,
 }
 
+impl Sell {
+    /// A sale of `amount` pump tokens for at least `min_sol_output` lamports.
+    pub fn new(amount: TokenAmount, min_sol_output: u64, slippage: i64) -> Result<Self, ErrorCode> {
+        Ok(Self {
+            amount: amount.pump_raw()?,
+            min_sol_output,
+            slippage,
+        })
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SellAccounts {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub mint: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub user: Pubkey,
 }
 
+/// Lowers `min_sol_output` by `slippage` percent.
+pub fn min_sol_output_with_slippage(min_sol_output: u64, slippage: i64) -> Result<u64, ErrorCode> {
+    if slippage < 0 {
+        return Err(ErrorCode::InvalidSlippage);
+    }
+
+    let min_cost = min_sol_output as u128;
+    let slippage = slippage as u128;
+
+    let slippage_amount = min_cost
+        .checked_mul(slippage)
+        .and_then(|v| v.checked_mul(10)) // for basis points scaling
+        .and_then(|v| v.checked_div(1000))
+        .ok_or(ErrorCode::Overflow)?; // still u128
+
+    min_cost
+        .checked_sub(slippage_amount)
+        .ok_or(ErrorCode::Overflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::Overflow)
+}
+
 pub fn sell_ix(
     rpc_client: &RpcClient,
-    program_id: &Pubkey,
+    config: &PumpConfig,
     accounts: SellAccounts,
     args: Sell,
 ) -> Result<Vec<Instruction>, ErrorCode> {
@@ -48,30 +90,29 @@ pub fn sell_ix(
         return Err(ErrorCode::InvalidSlippage);
     }
 
-    let bonding_curve = get_bonding_curve_pda(&accounts.mint);
-    let associated_bonding_curve = get_associated_bonding_curve(&accounts.mint);
-    let global_pda = get_global_pda();
-    let associated_user_token_account =
-        get_associated_token_address(&accounts.user, &accounts.mint);
-
-    let global_account_data = rpc_client
-        .get_account_data(&global_pda)
-        .map_err(|_| ErrorCode::GlobalNotFound)?;
+    let bonding_curve = get_bonding_curve_pda(config, &accounts.mint);
+    let global_pda = get_global_pda(config);
+    let token_program = fetch_token_program(rpc_client, &accounts.mint)?;
+    let associated_bonding_curve =
+        get_associated_bonding_curve(config, &accounts.mint, &token_program);
+    let associated_user_token_account = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.mint,
+        &token_program,
+    );
 
-    let global: Global = Global::deserialize(&mut &global_account_data[8..])
-        .map_err(|_| ErrorCode::DeserializationError)?;
+    let global = fetch_global(rpc_client, config)?;
 
     let fee_recipient = global.fee_recipient;
 
-    let bonding_curve_data = rpc_client
-        .get_account_data(&bonding_curve)
-        .map_err(|_| ErrorCode::BondingCurveNotFound)?;
+    let bonding_curve_account_data = fetch_bonding_curve(rpc_client, config, &accounts.mint)?;
 
-    let bonding_curve_account_data: BondingCurve =
-        BondingCurve::deserialize(&mut &bonding_curve_data[8..])
-            .map_err(|_| ErrorCode::DeserializationError)?;
+    // A missing ATA means the user holds nothing to sell
+    let user_token_balance = fetch_token_balance(rpc_client, &associated_user_token_account)?;
 
-    let creator_vault = get_creator_vault_pda(&bonding_curve_account_data.creator);
+    bonding_curve_account_data.validate_sell(args.amount, user_token_balance)?;
+
+    let creator_vault = get_creator_vault_pda(config, &bonding_curve_account_data.creator);
 
     let mut instructions: Vec<Instruction> = vec![];
 
@@ -85,25 +126,12 @@ pub fn sell_ix(
         AccountMeta::new(accounts.user, true),
         AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
         AccountMeta::new(creator_vault, false),
-        AccountMeta::new_readonly(TOKEN_PROGRAM, false),
-        AccountMeta::new_readonly(EVENT_AUTHORITY, false),
-        AccountMeta::new_readonly(PUMP_DOT_FUN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(config.event_authority, false),
+        AccountMeta::new_readonly(config.program_id, false),
     ];
 
-    let min_cost = args.min_sol_output as u128;
-    let slippage = args.slippage as u128;
-
-    let slippage_amount = min_cost
-        .checked_mul(slippage)
-        .and_then(|v| v.checked_mul(10)) // for basis points scaling
-        .and_then(|v| v.checked_div(1000))
-        .ok_or(ErrorCode::Overflow)?; // still u128
-
-    let new_sol_amount: u64 = min_cost
-        .checked_sub(slippage_amount)
-        .ok_or(ErrorCode::Overflow)?
-        .try_into()
-        .map_err(|_| ErrorCode::Overflow)?;
+    let new_sol_amount = min_sol_output_with_slippage(args.min_sol_output, args.slippage)?;
 
     let sell_args = SellArgs {
         amount: args.amount,
@@ -113,7 +141,7 @@ pub fn sell_ix(
     data.extend(sell_args.try_to_vec().unwrap());
 
     let sell_instruction = Instruction {
-        program_id: *program_id,
+        program_id: config.program_id,
         accounts: accounts_metas,
         data,
     };
@@ -0,0 +1,304 @@
+use anchor_lang::pubkey;
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, pubkey::Pubkey, signer::Signer, transaction::Transaction,
+};
+use solana_system_interface::instruction::transfer;
+
+use crate::errors::ErrorCode;
+
+/// Most transactions the block engine accepts in one bundle.
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// Mainnet tip accounts published by Jito; any one of them may be tipped.
+pub const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
+    pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+struct PendingTransaction<'a> {
+    instructions: Vec<Instruction>,
+    payer: &'a dyn Signer,
+    signers: Vec<&'a dyn Signer>,
+}
+
+/// Collects pump.fun transactions, for example a create followed by buys
+/// from several wallets, and turns them into tipped bundles.
+pub struct BundleBuilder<'a> {
+    tip_account: Pubkey,
+    tip_lamports: u64,
+    transactions: Vec<PendingTransaction<'a>>,
+}
+
+impl<'a> BundleBuilder<'a> {
+    pub fn new(tip_account: Pubkey, tip_lamports: u64) -> Self {
+        Self {
+            tip_account,
+            tip_lamports,
+            transactions: vec![],
+        }
+    }
+
+    /// Queues a transaction paid for by `payer`. `signers` lists any extra
+    /// signers, such as the mint keypair of a create.
+    pub fn add_transaction(
+        mut self,
+        instructions: Vec<Instruction>,
+        payer: &'a dyn Signer,
+        signers: &[&'a dyn Signer],
+    ) -> Self {
+        self.transactions.push(PendingTransaction {
+            instructions,
+            payer,
+            signers: signers.to_vec(),
+        });
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Signs the queued transactions, in order, into one bundle whose last
+    /// transaction carries the tip, paid by that transaction's payer. The
+    /// bundle lands all at once or not at all; more than
+    /// [`MAX_BUNDLE_TRANSACTIONS`] transactions is an error.
+    pub fn build(self, recent_blockhash: Hash) -> Result<Vec<Transaction>, ErrorCode> {
+        if self.transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+            return Err(ErrorCode::BundleTooLarge);
+        }
+        self.build_split(recent_blockhash)
+            .map(|bundles| bundles.into_iter().flatten().collect())
+    }
+
+    /// Like [`BundleBuilder::build`], but groups any number of transactions
+    /// into consecutive bundles of at most [`MAX_BUNDLE_TRANSACTIONS`], each
+    /// tipped on its last transaction. Every bundle lands on its own: a later
+    /// one can land without, or even before, an earlier one.
+    pub fn build_split(self, recent_blockhash: Hash) -> Result<Vec<Vec<Transaction>>, ErrorCode> {
+        let mut bundles = vec![];
+        let mut transactions = self.transactions.into_iter().peekable();
+        let mut bundle = vec![];
+
+        while let Some(mut pending) = transactions.next() {
+            let closes_bundle =
+                bundle.len() + 1 == MAX_BUNDLE_TRANSACTIONS || transactions.peek().is_none();
+            if closes_bundle {
+                pending.instructions.push(transfer(
+                    &pending.payer.pubkey(),
+                    &self.tip_account,
+                    self.tip_lamports,
+                ));
+            }
+
+            let payer = pending.payer.pubkey();
+            let mut signers = vec![pending.payer];
+            signers.extend(
+                pending
+                    .signers
+                    .into_iter()
+                    .filter(|signer| signer.pubkey() != payer),
+            );
+            let mut transaction = Transaction::new_with_payer(&pending.instructions, Some(&payer));
+            transaction
+                .try_sign(&signers, recent_blockhash)
+                .map_err(|_| ErrorCode::SigningFailed)?;
+            bundle.push(transaction);
+
+            if closes_bundle {
+                bundles.push(std::mem::take(&mut bundle));
+            }
+        }
+        Ok(bundles)
+    }
+}
+
+/// JSON-RPC request body for the block engine's `sendBundle` method.
+pub fn send_bundle_request(bundle: &[Transaction]) -> Result<Value, ErrorCode> {
+    let encoded = bundle
+        .iter()
+        .map(|transaction| {
+            bincode::serialize(transaction)
+                .map(|bytes| BASE64_STANDARD.encode(bytes))
+                .map_err(|_| ErrorCode::SerializationError)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "sendBundle",
+        "params": [encoded, { "encoding": "base64" }],
+    }))
+}
+
+/// Minimal block engine client speaking the `sendBundle` JSON-RPC method.
+pub struct JitoClient {
+    url: String,
+    http: reqwest::blocking::Client,
+}
+
+impl JitoClient {
+    /// `url` is the bundles endpoint, e.g.
+    /// `https://mainnet.block-engine.jito.wtf/api/v1/bundles`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            http: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// Submits one bundle and returns its id.
+    pub fn send_bundle(&self, bundle: &[Transaction]) -> Result<String, ErrorCode> {
+        let response: Value = self
+            .http
+            .post(&self.url)
+            .json(&send_bundle_request(bundle)?)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json())
+            .map_err(|_| ErrorCode::BundleRejected)?;
+
+        response["result"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or(ErrorCode::BundleRejected)
+    }
+
+    /// Submits bundles in order, stopping at the first rejection. Each bundle
+    /// is accepted or dropped independently of the others.
+    pub fn send_bundles(&self, bundles: &[Vec<Transaction>]) -> Result<Vec<String>, ErrorCode> {
+        bundles
+            .iter()
+            .map(|bundle| self.send_bundle(bundle))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Keypair;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    /// Answers one HTTP request with a `sendBundle` result and hands back the body it received.
+    fn spawn_block_engine(bundle_id: &'static str) -> (String, thread::JoinHandle<Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api/v1/bundles", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let reply = json!({ "jsonrpc": "2.0", "id": 1, "result": bundle_id }).to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                reply.len(),
+                reply
+            )
+            .unwrap();
+            serde_json::from_slice(&body).unwrap()
+        });
+        (url, handle)
+    }
+
+    fn transfers(wallets: &[Keypair], tip_account: Pubkey) -> BundleBuilder<'_> {
+        wallets.iter().fold(
+            BundleBuilder::new(tip_account, 10_000),
+            |builder, wallet| {
+                builder.add_transaction(
+                    vec![transfer(&wallet.pubkey(), &Pubkey::new_unique(), 1)],
+                    wallet,
+                    &[],
+                )
+            },
+        )
+    }
+
+    #[test]
+    fn test_build_rejects_more_than_one_bundle() {
+        let wallets: Vec<Keypair> = (0..6).map(|_| Keypair::new()).collect();
+        let tip_account = JITO_TIP_ACCOUNTS[0];
+
+        assert!(matches!(
+            transfers(&wallets, tip_account).build(Hash::new_unique()),
+            Err(ErrorCode::BundleTooLarge)
+        ));
+        let bundle = transfers(&wallets[..MAX_BUNDLE_TRANSACTIONS], tip_account)
+            .build(Hash::new_unique())
+            .unwrap();
+        assert_eq!(bundle.len(), MAX_BUNDLE_TRANSACTIONS);
+        assert!(bundle[4].message.account_keys.contains(&tip_account));
+    }
+
+    #[test]
+    fn test_build_split_tips_last_transaction_of_each_bundle() {
+        let wallets: Vec<Keypair> = (0..6).map(|_| Keypair::new()).collect();
+        let tip_account = JITO_TIP_ACCOUNTS[0];
+
+        let bundles = transfers(&wallets, tip_account)
+            .build_split(Hash::new_unique())
+            .unwrap();
+
+        assert_eq!(bundles.len(), 2);
+        assert_eq!(bundles[0].len(), MAX_BUNDLE_TRANSACTIONS);
+        assert_eq!(bundles[1].len(), 1);
+        for bundle in &bundles {
+            let (last, rest) = bundle.split_last().unwrap();
+            assert_eq!(last.message.instructions.len(), 2);
+            assert!(last.message.account_keys.contains(&tip_account));
+            assert!(rest
+                .iter()
+                .all(|transaction| transaction.message.instructions.len() == 1));
+        }
+    }
+
+    #[test]
+    fn test_send_bundle_posts_base64_transactions() {
+        let (url, server) = spawn_block_engine("bundle-1");
+        let payer = Keypair::new();
+        let bundle = BundleBuilder::new(JITO_TIP_ACCOUNTS[1], 1_000)
+            .add_transaction(vec![], &payer, &[])
+            .build(Hash::new_unique())
+            .unwrap();
+
+        let id = JitoClient::new(url).send_bundle(&bundle).unwrap();
+        let request = server.join().unwrap();
+
+        assert_eq!(id, "bundle-1");
+        assert_eq!(request["method"], "sendBundle");
+        assert_eq!(request["params"][1]["encoding"], "base64");
+        let sent = BASE64_STANDARD
+            .decode(request["params"][0][0].as_str().unwrap())
+            .unwrap();
+        let sent: Transaction = bincode::deserialize(&sent).unwrap();
+        assert_eq!(sent, bundle[0]);
+    }
+}
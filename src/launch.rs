@@ -0,0 +1,367 @@
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, packet::PACKET_DATA_SIZE, pubkey::Pubkey, signer::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::{
+    config::PumpConfig,
+    errors::ErrorCode,
+    instructions::{
+        buy::{build_buy_ix, max_sol_cost_with_slippage, BuyAccounts, BuyArgs},
+        create::{create_creator, create_token_program},
+    },
+    jito::BundleBuilder,
+    states::{BondingCurve, Global},
+};
+
+/// Bytes kept free in every packed transaction for the bundle tip transfer.
+const TIP_RESERVE: usize = 64;
+
+/// One wallet's share of a launch, sized against the simulated curve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedBuy {
+    pub user: Pubkey,
+    pub token_amount: u64,
+    /// SOL the buy costs at its position in the sequence, fees included.
+    pub max_sol_cost: u64,
+}
+
+/// Instructions packed into one transaction; `signers[0]` pays for it.
+#[derive(Debug, Clone)]
+pub struct LaunchTransaction {
+    pub instructions: Vec<Instruction>,
+    pub signers: Vec<Pubkey>,
+}
+
+/// Plays `budgets` (wallet, SOL to spend) against a fresh curve in order,
+/// moving the reserves after each buy so later wallets are priced where the
+/// earlier ones leave the curve.
+pub fn simulate_launch_buys(
+    global: &Global,
+    creator: &Pubkey,
+    budgets: &[(Pubkey, u64)],
+) -> Result<Vec<PlannedBuy>, ErrorCode> {
+    let fee_basis_points = global.trade_fee_basis_points(creator);
+    let mut curve = BondingCurve::initial(global, *creator);
+
+    budgets
+        .iter()
+        .map(|(user, sol_budget)| {
+            let token_amount = curve.buy_quote(*sol_budget, fee_basis_points);
+            let max_sol_cost = curve.buy_cost(token_amount, fee_basis_points)?;
+            curve.apply_buy(token_amount)?;
+            Ok(PlannedBuy {
+                user: *user,
+                token_amount,
+                max_sol_cost,
+            })
+        })
+        .collect()
+}
+
+fn serialized_len(instructions: &[Instruction], payer: &Pubkey) -> Result<usize, ErrorCode> {
+    let transaction = Transaction::new_with_payer(instructions, Some(payer));
+    bincode::serialized_size(&transaction)
+        .map(|size| size as usize)
+        .map_err(|_| ErrorCode::SerializationError)
+}
+
+/// Packs the create instruction and the planned buys into as few
+/// transactions as fit the packet size, keeping their order. `creator` pays
+/// for the create and `mint` co-signs it; it must be the creator named in
+/// `create_ix`, or the buys would be built against the wrong creator vault.
+pub fn pack_launch_transactions(
+    config: &PumpConfig,
+    create_ix: Instruction,
+    creator: &Pubkey,
+    mint: &Pubkey,
+    fee_recipient: &Pubkey,
+    buys: &[PlannedBuy],
+    slippage: i64,
+) -> Result<Vec<LaunchTransaction>, ErrorCode> {
+    if create_creator(&create_ix) != Some(*creator) {
+        return Err(ErrorCode::CreatorMismatch);
+    }
+    let token_program = create_token_program(&create_ix);
+    let mut transactions = vec![];
+    let mut current = LaunchTransaction {
+        instructions: vec![create_ix],
+        signers: vec![*creator, *mint],
+    };
+
+    for buy in buys {
+        let accounts = BuyAccounts {
+            mint: *mint,
+            user: buy.user,
+        };
+        let args = BuyArgs {
+            amount: buy.token_amount,
+            max_sol_cost: max_sol_cost_with_slippage(buy.max_sol_cost, slippage)?,
+        };
+        let buy_instructions = vec![
+            create_associated_token_account_idempotent(&buy.user, &buy.user, mint, &token_program),
+            build_buy_ix(
+                config,
+                &accounts,
+                &args,
+                fee_recipient,
+                creator,
+                &token_program,
+            ),
+        ];
+
+        let mut candidate = current.instructions.clone();
+        candidate.extend(buy_instructions.iter().cloned());
+        let payer = current.signers.first().copied().unwrap_or(buy.user);
+        if !current.instructions.is_empty()
+            && serialized_len(&candidate, &payer)? + TIP_RESERVE > PACKET_DATA_SIZE
+        {
+            transactions.push(std::mem::replace(
+                &mut current,
+                LaunchTransaction {
+                    instructions: vec![],
+                    signers: vec![],
+                },
+            ));
+        }
+
+        current.instructions.extend(buy_instructions);
+        if !current.signers.contains(&buy.user) {
+            current.signers.push(buy.user);
+        }
+    }
+
+    transactions.push(current);
+    Ok(transactions)
+}
+
+/// A buyer taking part in a launch and the SOL it may spend.
+pub struct LaunchBuyer<'a> {
+    pub wallet: &'a dyn Signer,
+    pub sol_budget: u64,
+}
+
+/// Coordinates a token launch with the first buys spread across many
+/// wallets. The creator wallet pays for the create and is recorded as the
+/// curve's creator; buyers are served in the order they were added.
+pub struct LaunchCoordinator<'a> {
+    config: PumpConfig,
+    create_ix: Instruction,
+    creator: &'a dyn Signer,
+    mint: &'a dyn Signer,
+    buyers: Vec<LaunchBuyer<'a>>,
+    slippage: i64,
+}
+
+impl<'a> LaunchCoordinator<'a> {
+    pub fn new(
+        config: &PumpConfig,
+        create_ix: Instruction,
+        creator: &'a dyn Signer,
+        mint: &'a dyn Signer,
+        slippage: i64,
+    ) -> Self {
+        Self {
+            config: *config,
+            create_ix,
+            creator,
+            mint,
+            buyers: vec![],
+            slippage,
+        }
+    }
+
+    pub fn add_buyer(mut self, wallet: &'a dyn Signer, sol_budget: u64) -> Self {
+        self.buyers.push(LaunchBuyer { wallet, sol_budget });
+        self
+    }
+
+    /// Simulates the buys and packs them behind the create instruction.
+    pub fn plan(
+        &self,
+        global: &Global,
+    ) -> Result<(Vec<PlannedBuy>, Vec<LaunchTransaction>), ErrorCode> {
+        let creator = self.creator.pubkey();
+        let budgets: Vec<(Pubkey, u64)> = self
+            .buyers
+            .iter()
+            .map(|buyer| (buyer.wallet.pubkey(), buyer.sol_budget))
+            .collect();
+        let buys = simulate_launch_buys(global, &creator, &budgets)?;
+        let transactions = pack_launch_transactions(
+            &self.config,
+            self.create_ix.clone(),
+            &creator,
+            &self.mint.pubkey(),
+            &global.fee_recipient,
+            &buys,
+            self.slippage,
+        )?;
+        Ok((buys, transactions))
+    }
+
+    /// Plans the launch and signs it into one tipped bundle, so the create
+    /// and every buy land together or not at all. Fails with
+    /// `BundleTooLarge` when the buys need more than one bundle; use
+    /// [`LaunchCoordinator::build_split_bundles`] to accept splitting.
+    pub fn build_bundle(
+        &self,
+        global: &Global,
+        tip_account: Pubkey,
+        tip_lamports: u64,
+        recent_blockhash: Hash,
+    ) -> Result<Vec<Transaction>, ErrorCode> {
+        self.bundle_builder(global, tip_account, tip_lamports)?
+            .build(recent_blockhash)
+    }
+
+    /// Like [`LaunchCoordinator::build_bundle`], but splits the launch into
+    /// as many tipped bundles as it needs, the create in the first. Bundles
+    /// land independently: the buys in later bundles can land without the
+    /// create, or be tried before it and fail, so send them in order and
+    /// check that the first one landed before relying on the rest.
+    pub fn build_split_bundles(
+        &self,
+        global: &Global,
+        tip_account: Pubkey,
+        tip_lamports: u64,
+        recent_blockhash: Hash,
+    ) -> Result<Vec<Vec<Transaction>>, ErrorCode> {
+        self.bundle_builder(global, tip_account, tip_lamports)?
+            .build_split(recent_blockhash)
+    }
+
+    fn bundle_builder(
+        &self,
+        global: &Global,
+        tip_account: Pubkey,
+        tip_lamports: u64,
+    ) -> Result<BundleBuilder<'a>, ErrorCode> {
+        let (_, transactions) = self.plan(global)?;
+
+        let mut known: Vec<&'a dyn Signer> = vec![self.creator, self.mint];
+        known.extend(self.buyers.iter().map(|buyer| buyer.wallet));
+        let find = |pubkey: &Pubkey| {
+            known
+                .iter()
+                .find(|signer| signer.pubkey() == *pubkey)
+                .copied()
+                .ok_or(ErrorCode::SigningFailed)
+        };
+
+        let mut builder = BundleBuilder::new(tip_account, tip_lamports);
+        for transaction in transactions {
+            let signers = transaction
+                .signers
+                .iter()
+                .map(&find)
+                .collect::<Result<Vec<_>, _>>()?;
+            builder = builder.add_transaction(transaction.instructions, signers[0], &signers[1..]);
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::create::{create_ix, CreateAccounts, CreateArgs},
+        jito::MAX_BUNDLE_TRANSACTIONS,
+        states::bonding_curve::tests::global,
+    };
+    use solana_sdk::signature::Keypair;
+
+    #[test]
+    fn test_later_wallets_get_fewer_tokens_for_the_same_budget() {
+        let budgets: Vec<(Pubkey, u64)> = (0..3)
+            .map(|_| (Pubkey::new_unique(), 1_000_000_000))
+            .collect();
+        let buys = simulate_launch_buys(&global(), &Pubkey::new_unique(), &budgets).unwrap();
+
+        assert_eq!(buys.len(), 3);
+        assert!(buys[0].token_amount > buys[1].token_amount);
+        assert!(buys[1].token_amount > buys[2].token_amount);
+        assert!(buys.iter().all(|buy| buy.max_sol_cost <= 1_000_000_000 + 2));
+    }
+
+    fn launch_create_ix(
+        config: &PumpConfig,
+        mint: &Pubkey,
+        user: &Pubkey,
+        creator: &Pubkey,
+    ) -> Instruction {
+        create_ix(
+            config,
+            CreateAccounts {
+                mint: *mint,
+                user: *user,
+            },
+            CreateArgs {
+                name: "Launch".to_string(),
+                symbol: "LNCH".to_string(),
+                uri: "https://example.com/metadata.json".to_string(),
+                creator: *creator,
+            },
+        )
+    }
+
+    #[test]
+    fn test_bundles_fit_packet_size() {
+        let creator = Keypair::new();
+        let mint = Keypair::new();
+        let wallets: Vec<Keypair> = (0..24).map(|_| Keypair::new()).collect();
+        let config = PumpConfig::default();
+        let create = launch_create_ix(
+            &config,
+            &mint.pubkey(),
+            &creator.pubkey(),
+            &creator.pubkey(),
+        );
+
+        let coordinator = wallets.iter().fold(
+            LaunchCoordinator::new(&config, create, &creator, &mint, 0),
+            |coordinator, wallet| coordinator.add_buyer(wallet, 500_000_000),
+        );
+        let (_, transactions) = coordinator.plan(&global()).unwrap();
+        let bundles = coordinator
+            .build_split_bundles(&global(), Pubkey::new_unique(), 10_000, Hash::new_unique())
+            .unwrap();
+
+        assert!(transactions.len() > MAX_BUNDLE_TRANSACTIONS);
+        assert!(transactions.len() < wallets.len());
+        assert!(matches!(
+            coordinator.build_bundle(&global(), Pubkey::new_unique(), 10_000, Hash::new_unique()),
+            Err(ErrorCode::BundleTooLarge)
+        ));
+        let sent: Vec<&Transaction> = bundles.iter().flatten().collect();
+        assert_eq!(sent.len(), transactions.len());
+        for transaction in sent {
+            assert!(bincode::serialized_size(transaction).unwrap() as usize <= PACKET_DATA_SIZE);
+            assert!(transaction.is_signed());
+        }
+    }
+
+    #[test]
+    fn test_plan_rejects_a_create_for_another_creator() {
+        let creator = Keypair::new();
+        let mint = Keypair::new();
+        let buyer = Keypair::new();
+        let config = PumpConfig::default();
+        let create = launch_create_ix(
+            &config,
+            &mint.pubkey(),
+            &creator.pubkey(),
+            &Pubkey::new_unique(),
+        );
+
+        let coordinator = LaunchCoordinator::new(&config, create, &creator, &mint, 0)
+            .add_buyer(&buyer, 500_000_000);
+
+        assert!(matches!(
+            coordinator.plan(&global()),
+            Err(ErrorCode::CreatorMismatch)
+        ));
+    }
+}
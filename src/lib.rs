@@ -1,26 +1,59 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use anchor_lang::pubkey;
+use config::PumpConfig;
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::{
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount, Message},
+    pubkey::Pubkey,
+};
+use states::BondingCurve;
+use subscriptions::{CurveSubscription, LaunchSubscription};
 
 pub mod instructions;
 pub use instructions::*;
+pub mod amount;
+pub mod candles;
+pub mod config;
 pub mod constants;
 pub mod errors;
+pub mod events;
+pub mod fetch;
+pub mod holders;
+pub mod jito;
+pub mod launch;
+pub mod lookup_table;
+pub mod metadata;
+pub mod nonce;
+pub mod offline;
 use crate::errors::ErrorCode;
 pub mod pda;
+pub mod pnl;
+pub mod portfolio;
+pub mod pump_amm;
+pub mod resolver;
+pub mod router;
+#[cfg(feature = "serde")]
+pub mod serde_helpers;
 pub mod states;
+pub mod subscriptions;
 
 pub const PUMP_DOT_FUN_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
 
 pub struct PumpDotFunSdk {
     pub rpc: Arc<RpcClient>,
+    pub config: PumpConfig,
 }
 
 impl PumpDotFunSdk {
     pub fn new(rpc: Arc<RpcClient>) -> Self {
-        Self { rpc }
+        Self::with_config(rpc, PumpConfig::mainnet())
+    }
+
+    /// Targets the deployment described by `config`, e.g. a local validator.
+    pub fn with_config(rpc: Arc<RpcClient>, config: PumpConfig) -> Self {
+        Self { rpc, config }
     }
 
     pub fn create(
@@ -28,7 +61,16 @@ impl PumpDotFunSdk {
         accounts: instructions::create::CreateAccounts,
         args: instructions::create::CreateArgs,
     ) -> Instruction {
-        instructions::create::create_ix(&PUMP_DOT_FUN_PROGRAM_ID, accounts, args)
+        instructions::create::create_ix(&self.config, accounts, args)
+    }
+
+    /// Like [`Self::create`], but mints a Token-2022 token.
+    pub fn create_v2(
+        &self,
+        accounts: instructions::create::CreateAccounts,
+        args: instructions::create::CreateArgs,
+    ) -> Instruction {
+        instructions::create::create_v2_ix(&self.config, accounts, args)
     }
 
     pub fn buy(
@@ -36,7 +78,7 @@ impl PumpDotFunSdk {
         accounts: instructions::buy::BuyAccounts,
         args: instructions::buy::Buy,
     ) -> Result<Vec<Instruction>, ErrorCode> {
-        instructions::buy::buy_ix(&self.rpc, &PUMP_DOT_FUN_PROGRAM_ID, accounts, args)
+        instructions::buy::buy_ix(&self.rpc, &self.config, accounts, args)
     }
 
     pub fn sell(
@@ -44,6 +86,273 @@ impl PumpDotFunSdk {
         accounts: instructions::sell::SellAccounts,
         args: instructions::sell::Sell,
     ) -> Result<Vec<Instruction>, ErrorCode> {
-        instructions::sell::sell_ix(&self.rpc, &PUMP_DOT_FUN_PROGRAM_ID, accounts, args)
+        instructions::sell::sell_ix(&self.rpc, &self.config, accounts, args)
+    }
+
+    /// Builds a buy as a v0 message that resolves the static pump.fun
+    /// accounts through `lookup_tables`.
+    pub fn buy_v0(
+        &self,
+        accounts: instructions::buy::BuyAccounts,
+        args: instructions::buy::Buy,
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<v0::Message, ErrorCode> {
+        let payer = accounts.user;
+        let instructions = self.buy(accounts, args)?;
+        self.compile_v0(&payer, &instructions, lookup_tables)
+    }
+
+    /// Builds a sell as a v0 message that resolves the static pump.fun
+    /// accounts through `lookup_tables`.
+    pub fn sell_v0(
+        &self,
+        accounts: instructions::sell::SellAccounts,
+        args: instructions::sell::Sell,
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<v0::Message, ErrorCode> {
+        let payer = accounts.user;
+        let instructions = self.sell(accounts, args)?;
+        self.compile_v0(&payer, &instructions, lookup_tables)
+    }
+
+    /// Compiles `instructions` against the latest blockhash into a v0 message.
+    pub fn compile_v0(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<v0::Message, ErrorCode> {
+        let recent_blockhash = self
+            .rpc
+            .get_latest_blockhash()
+            .map_err(|_| ErrorCode::BlockhashNotFound)?;
+        lookup_table::compile_v0_message(payer, instructions, lookup_tables, recent_blockhash)
+    }
+
+    /// Wraps `instructions` in an unsigned message against the latest
+    /// blockhash, for signing by an external signer.
+    pub fn signing_request(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+    ) -> Result<offline::SigningRequest, ErrorCode> {
+        let recent_blockhash = self
+            .rpc
+            .get_latest_blockhash()
+            .map_err(|_| ErrorCode::BlockhashNotFound)?;
+        Ok(offline::SigningRequest::from_instructions(
+            payer,
+            instructions,
+            recent_blockhash,
+        ))
+    }
+
+    /// Instructions creating a lookup table holding the static pump.fun
+    /// accounts, and the table's address.
+    pub fn create_lookup_table(
+        &self,
+        authority: &Pubkey,
+        payer: &Pubkey,
+    ) -> Result<(Pubkey, Vec<Instruction>), ErrorCode> {
+        let global = fetch::fetch_global(&self.rpc, &self.config)?;
+        let recent_slot = self
+            .rpc
+            .get_slot()
+            .map_err(|_| ErrorCode::AccountFetchFailed)?;
+        Ok(lookup_table::create_lookup_table_ixs(
+            authority,
+            payer,
+            recent_slot,
+            &lookup_table::static_lookup_addresses(&self.config, &global),
+        ))
+    }
+
+    /// Instructions adding any static pump.fun account missing from `table`,
+    /// for example after the fee recipients change.
+    pub fn extend_lookup_table(
+        &self,
+        table: &AddressLookupTableAccount,
+        authority: &Pubkey,
+        payer: &Pubkey,
+    ) -> Result<Vec<Instruction>, ErrorCode> {
+        let global = fetch::fetch_global(&self.rpc, &self.config)?;
+        Ok(lookup_table::extend_lookup_table_ixs(
+            table,
+            authority,
+            payer,
+            &lookup_table::static_lookup_addresses(&self.config, &global),
+        ))
+    }
+
+    /// Builds a buy as a durable message that stays valid until `nonce` is
+    /// advanced, for signing ahead of time.
+    pub fn buy_durable(
+        &self,
+        accounts: instructions::buy::BuyAccounts,
+        args: instructions::buy::Buy,
+        nonce: &nonce::NonceInfo,
+    ) -> Result<Message, ErrorCode> {
+        let payer = accounts.user;
+        let instructions = self.buy(accounts, args)?;
+        Ok(nonce::durable_message(&payer, nonce, &instructions))
+    }
+
+    /// Builds a sell as a durable message that stays valid until `nonce` is
+    /// advanced, for signing ahead of time.
+    pub fn sell_durable(
+        &self,
+        accounts: instructions::sell::SellAccounts,
+        args: instructions::sell::Sell,
+        nonce: &nonce::NonceInfo,
+    ) -> Result<Message, ErrorCode> {
+        let payer = accounts.user;
+        let instructions = self.sell(accounts, args)?;
+        Ok(nonce::durable_message(&payer, nonce, &instructions))
+    }
+
+    /// Instructions creating a rent-exempt nonce account controlled by `authority`.
+    pub fn create_nonce_account(
+        &self,
+        payer: &Pubkey,
+        nonce: &Pubkey,
+        authority: &Pubkey,
+    ) -> Result<Vec<Instruction>, ErrorCode> {
+        let lamports = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(nonce::NONCE_ACCOUNT_LEN)
+            .map_err(|_| ErrorCode::AccountFetchFailed)?;
+        Ok(nonce::create_nonce_account_ixs(
+            payer, nonce, authority, lamports,
+        ))
+    }
+
+    pub fn fetch_nonce(&self, address: &Pubkey) -> Result<nonce::NonceInfo, ErrorCode> {
+        nonce::fetch_nonce(&self.rpc, address)
+    }
+
+    /// Claims the creator fees accrued by `creator`'s launches.
+    pub fn collect_creator_fee(&self, creator: &Pubkey) -> Instruction {
+        instructions::collect_creator_fee::collect_creator_fee_ix(
+            &self.config,
+            instructions::collect_creator_fee::CollectCreatorFeeAccounts { creator: *creator },
+        )
+    }
+
+    /// Buys a migrated token from its pump AMM pool.
+    pub fn amm_buy(
+        &self,
+        accounts: instructions::buy::BuyAccounts,
+        args: instructions::buy::Buy,
+    ) -> Result<Vec<Instruction>, ErrorCode> {
+        pump_amm::buy_ix(&self.rpc, &self.config, accounts, args)
+    }
+
+    /// Sells a migrated token into its pump AMM pool.
+    pub fn amm_sell(
+        &self,
+        accounts: instructions::sell::SellAccounts,
+        args: instructions::sell::Sell,
+    ) -> Result<Vec<Instruction>, ErrorCode> {
+        pump_amm::sell_ix(&self.rpc, &self.config, accounts, args)
+    }
+
+    /// Buys or sells `mint` wherever it currently trades: on the bonding curve
+    /// while it is active, on the pump AMM pool once it has completed.
+    pub fn swap(
+        &self,
+        accounts: router::SwapAccounts,
+        side: router::SwapSide,
+        amount: u64,
+        slippage: i64,
+    ) -> Result<router::SwapPlan, ErrorCode> {
+        router::swap(&self.rpc, &self.config, accounts, side, amount, slippage)
+    }
+
+    /// Fetches the bonding curves of many mints in batched, concurrent RPC calls.
+    pub fn fetch_bonding_curves(
+        &self,
+        mints: &[Pubkey],
+    ) -> HashMap<Pubkey, Result<Option<BondingCurve>, ErrorCode>> {
+        fetch::fetch_bonding_curves(&self.rpc, &self.config, mints)
+    }
+
+    /// Fetches `mint`'s name, symbol, uri and creators, and the off-chain
+    /// JSON when `fetch_json` is set.
+    pub fn fetch_token_metadata(
+        &self,
+        mint: &Pubkey,
+        fetch_json: bool,
+    ) -> Result<metadata::MintMetadata, ErrorCode> {
+        metadata::fetch_token_metadata(&self.rpc, &self.config, mint, fetch_json)
+    }
+
+    /// Fetches the metadata of many mints in batched RPC calls.
+    pub fn fetch_token_metadatas(
+        &self,
+        mints: &[Pubkey],
+        fetch_json: bool,
+    ) -> HashMap<Pubkey, Result<Option<metadata::MintMetadata>, ErrorCode>> {
+        metadata::fetch_token_metadatas(&self.rpc, &self.config, mints, fetch_json)
+    }
+
+    /// Reports holder concentration, the creator's stake and top holders
+    /// funded by the same wallet for `mint`.
+    pub fn analyze_holders(
+        &self,
+        mint: &Pubkey,
+        options: holders::HolderAnalysisOptions,
+    ) -> Result<holders::HolderReport, ErrorCode> {
+        holders::analyze_holders(&self.rpc, &self.config, mint, options)
+    }
+
+    /// Lists `user`'s pump.fun token balances with their curve value.
+    pub fn portfolio(&self, user: &Pubkey) -> Result<portfolio::Portfolio, ErrorCode> {
+        portfolio::portfolio(&self.rpc, &self.config, user)
+    }
+
+    /// Realized and unrealized PnL of `user` per mint, from the trades in
+    /// its last `history_limit` signatures.
+    pub fn pnl(
+        &self,
+        user: &Pubkey,
+        method: pnl::CostBasisMethod,
+        history_limit: usize,
+    ) -> Result<pnl::PnlReport, ErrorCode> {
+        pnl::fetch_pnl(&self.rpc, &self.config, user, method, history_limit)
+    }
+
+    /// Lists every bonding curve matching `filter`, keyed by curve address.
+    pub fn list_bonding_curves(
+        &self,
+        filter: fetch::BondingCurveFilter,
+    ) -> Result<HashMap<Pubkey, BondingCurve>, ErrorCode> {
+        fetch::list_bonding_curves(&self.rpc, &self.config, filter)
+    }
+
+    /// Finds the mint behind a bonding curve address.
+    pub fn resolve_mint(&self, bonding_curve: &Pubkey) -> Result<Pubkey, ErrorCode> {
+        resolver::resolve_mint(&self.rpc, &self.config, bonding_curve)
+    }
+
+    /// Streams decoded snapshots of `mint`'s bonding curve over the websocket
+    /// endpoint that pairs with this SDK's RPC URL.
+    pub fn subscribe_curve(&self, mint: &Pubkey) -> Result<CurveSubscription, ErrorCode> {
+        subscriptions::subscribe_curve(
+            &subscriptions::websocket_url(&self.rpc.url()),
+            &self.config,
+            self.rpc.clone(),
+            mint,
+            self.rpc.commitment(),
+        )
+    }
+
+    /// Streams newly launched tokens as they are created on-chain.
+    pub fn subscribe_launches(&self) -> Result<LaunchSubscription, ErrorCode> {
+        subscriptions::subscribe_launches(
+            &subscriptions::websocket_url(&self.rpc.url()),
+            &self.config,
+            Some(self.rpc.clone()),
+            self.rpc.commitment(),
+        )
     }
 }
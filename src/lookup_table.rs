@@ -0,0 +1,152 @@
+use solana_address_lookup_table_interface::{
+    instruction::{create_lookup_table, extend_lookup_table},
+    state::AddressLookupTable,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    config::PumpConfig,
+    constants::{ASSOCIATED_TOKEN_PROGRAM, SYSTEM_PROGRAM, TOKEN_2022_PROGRAM, TOKEN_PROGRAM},
+    errors::ErrorCode,
+    pda::get_global_pda,
+    states::Global,
+};
+
+/// Addresses per `extend` instruction, small enough to fit a legacy transaction.
+pub const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+/// Accounts every pump.fun trade references regardless of the mint: the
+/// program and its event authority, the global PDA, the system, SPL Token,
+/// Token-2022 and associated token programs, and every configured fee
+/// recipient.
+pub fn static_lookup_addresses(config: &PumpConfig, global: &Global) -> Vec<Pubkey> {
+    let mut addresses = vec![
+        config.program_id,
+        config.event_authority,
+        get_global_pda(config),
+        SYSTEM_PROGRAM,
+        TOKEN_PROGRAM,
+        TOKEN_2022_PROGRAM,
+        ASSOCIATED_TOKEN_PROGRAM,
+        global.fee_recipient,
+    ];
+    for recipient in global.fee_recipients {
+        if recipient != Pubkey::default() && !addresses.contains(&recipient) {
+            addresses.push(recipient);
+        }
+    }
+    addresses
+}
+
+/// Instructions that create a lookup table and fill it with `addresses`.
+/// The first instruction creates the table; send the `extend` ones after it,
+/// splitting them across transactions if needed.
+pub fn create_lookup_table_ixs(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+    addresses: &[Pubkey],
+) -> (Pubkey, Vec<Instruction>) {
+    let (create_ix, table) = create_lookup_table(*authority, *payer, recent_slot);
+    let mut instructions = vec![create_ix];
+    instructions.extend(
+        addresses
+            .chunks(MAX_ADDRESSES_PER_EXTEND)
+            .map(|chunk| extend_lookup_table(table, *authority, Some(*payer), chunk.to_vec())),
+    );
+    (table, instructions)
+}
+
+/// `extend` instructions adding whichever of `addresses` the table lacks.
+pub fn extend_lookup_table_ixs(
+    table: &AddressLookupTableAccount,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    addresses: &[Pubkey],
+) -> Vec<Instruction> {
+    let missing: Vec<Pubkey> = addresses
+        .iter()
+        .filter(|address| !table.addresses.contains(address))
+        .copied()
+        .collect();
+    missing
+        .chunks(MAX_ADDRESSES_PER_EXTEND)
+        .map(|chunk| extend_lookup_table(table.key, *authority, Some(*payer), chunk.to_vec()))
+        .collect()
+}
+
+pub fn fetch_lookup_table(
+    rpc_client: &RpcClient,
+    address: &Pubkey,
+) -> Result<AddressLookupTableAccount, ErrorCode> {
+    let data = rpc_client
+        .get_account_data(address)
+        .map_err(|_| ErrorCode::LookupTableNotFound)?;
+    let table =
+        AddressLookupTable::deserialize(&data).map_err(|_| ErrorCode::DeserializationError)?;
+    Ok(AddressLookupTableAccount {
+        key: *address,
+        addresses: table.addresses.to_vec(),
+    })
+}
+
+/// Compiles `instructions` into a v0 message that resolves accounts through
+/// `lookup_tables` wherever it can.
+pub fn compile_v0_message(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<v0::Message, ErrorCode> {
+    v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)
+        .map_err(|_| ErrorCode::MessageCompileFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::AccountMeta;
+
+    #[test]
+    fn test_compile_v0_message_uses_lookup_table() {
+        let config = &PumpConfig::default();
+        let payer = Pubkey::new_unique();
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![
+                config.event_authority,
+                get_global_pda(config),
+                SYSTEM_PROGRAM,
+            ],
+        };
+        let instruction = Instruction {
+            program_id: config.program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(get_global_pda(config), false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
+                AccountMeta::new_readonly(config.event_authority, false),
+            ],
+            data: vec![],
+        };
+
+        let message = compile_v0_message(
+            &payer,
+            &[instruction],
+            std::slice::from_ref(&table),
+            Hash::default(),
+        )
+        .unwrap();
+
+        assert_eq!(message.account_keys, vec![payer, config.program_id]);
+        assert_eq!(message.address_table_lookups.len(), 1);
+        assert_eq!(message.address_table_lookups[0].account_key, table.key);
+        assert!(extend_lookup_table_ixs(&table, &payer, &payer, &[SYSTEM_PROGRAM]).is_empty());
+    }
+}
@@ -0,0 +1,802 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use borsh::BorshDeserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, hash::hash, pubkey::Pubkey};
+
+use crate::{
+    config::PumpConfig, constants::TOKEN_2022_PROGRAM, errors::ErrorCode,
+    fetch::fetch_multiple_accounts, pda::get_metadata_pda,
+};
+
+/// Longest name, in bytes, Metaplex stores on chain.
+pub const MAX_NAME_LENGTH: usize = 32;
+/// Longest symbol, in bytes, Metaplex stores on chain.
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+/// Longest URI, in bytes, Metaplex stores on chain.
+pub const MAX_URI_LENGTH: usize = 200;
+
+/// How long HTTP clients in this module wait to connect.
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long an upload request may take as a whole.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The off-chain JSON `CreateArgs::uri` points to, in the Metaplex token
+/// standard layout with pump.fun's social links alongside.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub symbol: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub twitter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telegram: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+}
+
+impl TokenMetadata {
+    pub fn builder(name: impl Into<String>, symbol: impl Into<String>) -> TokenMetadataBuilder {
+        TokenMetadataBuilder {
+            metadata: TokenMetadata {
+                name: name.into(),
+                symbol: symbol.into(),
+                description: String::new(),
+                image: None,
+                twitter: None,
+                telegram: None,
+                website: None,
+            },
+        }
+    }
+
+    /// Checks the fields Metaplex copies on chain against its limits.
+    pub fn validate(&self) -> Result<(), ErrorCode> {
+        let name = self.name.trim();
+        let symbol = self.symbol.trim();
+        if name.is_empty()
+            || symbol.is_empty()
+            || name.len() > MAX_NAME_LENGTH
+            || symbol.len() > MAX_SYMBOL_LENGTH
+            || self
+                .image
+                .as_ref()
+                .is_some_and(|image| image.len() > MAX_URI_LENGTH)
+        {
+            return Err(ErrorCode::InvalidMetadata);
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, ErrorCode> {
+        serde_json::to_vec_pretty(self).map_err(|_| ErrorCode::SerializationError)
+    }
+
+    /// Uploads `image` (if any) and then the JSON through `backend`, and
+    /// returns the URI to put in `CreateArgs::uri`. Both files are named
+    /// after a hash of their content, so tokens never overwrite each other.
+    pub fn upload(
+        mut self,
+        backend: &dyn UploadBackend,
+        image: Option<&TokenImage>,
+    ) -> Result<String, ErrorCode> {
+        if let Some(image) = image {
+            let extension = Path::new(&image.file_name)
+                .extension()
+                .and_then(|ext| ext.to_str());
+            let file_name = content_file_name(&image.bytes, extension);
+            self.image = Some(backend.upload(&file_name, &image.content_type, &image.bytes)?);
+        }
+        self.validate()?;
+
+        let json = self.to_json()?;
+        let file_name = content_file_name(&json, Some("json"));
+        let uri = backend.upload(&file_name, "application/json", &json)?;
+        if uri.len() > MAX_URI_LENGTH {
+            return Err(ErrorCode::InvalidMetadata);
+        }
+        Ok(uri)
+    }
+}
+
+/// Builds a [`TokenMetadata`], validating it once every field is set.
+#[derive(Debug, Clone)]
+pub struct TokenMetadataBuilder {
+    metadata: TokenMetadata,
+}
+
+impl TokenMetadataBuilder {
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.metadata.description = description.into();
+        self
+    }
+
+    /// An image that is already hosted; leave unset to upload one with
+    /// [`TokenMetadata::upload`].
+    pub fn image(mut self, uri: impl Into<String>) -> Self {
+        self.metadata.image = Some(uri.into());
+        self
+    }
+
+    pub fn twitter(mut self, url: impl Into<String>) -> Self {
+        self.metadata.twitter = Some(url.into());
+        self
+    }
+
+    pub fn telegram(mut self, url: impl Into<String>) -> Self {
+        self.metadata.telegram = Some(url.into());
+        self
+    }
+
+    pub fn website(mut self, url: impl Into<String>) -> Self {
+        self.metadata.website = Some(url.into());
+        self
+    }
+
+    pub fn build(self) -> Result<TokenMetadata, ErrorCode> {
+        self.metadata.validate()?;
+        Ok(self.metadata)
+    }
+}
+
+/// Image file to upload next to the metadata JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenImage {
+    pub file_name: String,
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+impl TokenImage {
+    /// Reads an image from disk, taking its content type from the extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ErrorCode> {
+        let path = path.as_ref();
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or(ErrorCode::InvalidMetadata)?
+            .to_string();
+        let content_type = match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("png") => "image/png",
+            Some("jpg" | "jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            Some("svg") => "image/svg+xml",
+            _ => "application/octet-stream",
+        };
+        let bytes = fs::read(path).map_err(|_| ErrorCode::InvalidMetadata)?;
+        Ok(Self {
+            file_name,
+            content_type: content_type.to_string(),
+            bytes,
+        })
+    }
+}
+
+/// `{sha256 of bytes}.{extension}`, a file name unique to its content.
+fn content_file_name(bytes: &[u8], extension: Option<&str>) -> String {
+    match extension {
+        Some(extension) => format!("{}.{}", hash(bytes), extension.to_ascii_lowercase()),
+        None => hash(bytes).to_string(),
+    }
+}
+
+/// Rejects file names that are empty, relative path components or contain
+/// path separators, so a backend only ever writes directly under its root.
+fn check_file_name(file_name: &str) -> Result<(), ErrorCode> {
+    if file_name.is_empty()
+        || file_name == "."
+        || file_name == ".."
+        || file_name.contains(['/', '\\', '"', '\0'])
+    {
+        return Err(ErrorCode::InvalidMetadata);
+    }
+    Ok(())
+}
+
+fn upload_http_client() -> reqwest::blocking::Client {
+    reqwest::blocking::Client::builder()
+        .connect_timeout(HTTP_CONNECT_TIMEOUT)
+        .timeout(UPLOAD_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// Somewhere to store the image and metadata JSON. Returns the public URI
+/// of each stored file. The built-in backends reject file names containing
+/// path separators.
+pub trait UploadBackend {
+    fn upload(
+        &self,
+        file_name: &str,
+        content_type: &str,
+        bytes: &[u8],
+    ) -> Result<String, ErrorCode>;
+}
+
+/// Adds files through an IPFS node's HTTP API (`/api/v0/add`), pinning them,
+/// and serves them from a gateway.
+pub struct IpfsUploadBackend {
+    api_url: String,
+    gateway_url: String,
+    http: reqwest::blocking::Client,
+}
+
+impl IpfsUploadBackend {
+    /// `api_url` is the node's API root, e.g. `http://127.0.0.1:5001`.
+    pub fn new(api_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            gateway_url: "https://ipfs.io/ipfs".to_string(),
+            http: upload_http_client(),
+        }
+    }
+
+    /// Gateway the returned URIs point at; defaults to `https://ipfs.io/ipfs`.
+    pub fn with_gateway(mut self, gateway_url: impl Into<String>) -> Self {
+        self.gateway_url = gateway_url.into();
+        self
+    }
+}
+
+impl UploadBackend for IpfsUploadBackend {
+    fn upload(
+        &self,
+        file_name: &str,
+        content_type: &str,
+        bytes: &[u8],
+    ) -> Result<String, ErrorCode> {
+        check_file_name(file_name)?;
+        let boundary = format!("pumpfun-{:016x}", boundary_for(bytes));
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: {content_type}\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(bytes);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        let response: Value = self
+            .http
+            .post(format!(
+                "{}/api/v0/add?pin=true",
+                self.api_url.trim_end_matches('/')
+            ))
+            .header(
+                "Content-Type",
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(body)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json())
+            .map_err(|_| ErrorCode::UploadFailed)?;
+
+        let hash = response["Hash"].as_str().ok_or(ErrorCode::UploadFailed)?;
+        Ok(format!(
+            "{}/{}",
+            self.gateway_url.trim_end_matches('/'),
+            hash
+        ))
+    }
+}
+
+/// Multipart boundary derived from the payload, so it is stable for a file
+/// and vanishingly unlikely to occur inside it.
+fn boundary_for(bytes: &[u8]) -> u64 {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// PUTs each file to `{base_url}/{file_name}` and returns that URL, which
+/// suits WebDAV servers, presigned-bucket proxies and similar stores.
+pub struct HttpUploadBackend {
+    base_url: String,
+    bearer_token: Option<String>,
+    http: reqwest::blocking::Client,
+}
+
+impl HttpUploadBackend {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            bearer_token: None,
+            http: upload_http_client(),
+        }
+    }
+
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+}
+
+impl UploadBackend for HttpUploadBackend {
+    fn upload(
+        &self,
+        file_name: &str,
+        content_type: &str,
+        bytes: &[u8],
+    ) -> Result<String, ErrorCode> {
+        check_file_name(file_name)?;
+        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), file_name);
+        let mut request = self
+            .http
+            .put(&url)
+            .header("Content-Type", content_type)
+            .body(bytes.to_vec());
+        if let Some(token) = &self.bearer_token {
+            request = request.bearer_auth(token);
+        }
+        request
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|_| ErrorCode::UploadFailed)?;
+        Ok(url)
+    }
+}
+
+/// Writes files into a local directory, e.g. one a web server already
+/// serves. URIs are `{base_uri}/{file_name}`.
+pub struct LocalUploadBackend {
+    dir: PathBuf,
+    base_uri: String,
+}
+
+impl LocalUploadBackend {
+    pub fn new(dir: impl Into<PathBuf>, base_uri: impl Into<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            base_uri: base_uri.into(),
+        }
+    }
+}
+
+impl UploadBackend for LocalUploadBackend {
+    fn upload(
+        &self,
+        file_name: &str,
+        _content_type: &str,
+        bytes: &[u8],
+    ) -> Result<String, ErrorCode> {
+        check_file_name(file_name)?;
+        fs::create_dir_all(&self.dir).map_err(|_| ErrorCode::UploadFailed)?;
+        fs::write(self.dir.join(file_name), bytes).map_err(|_| ErrorCode::UploadFailed)?;
+        Ok(format!(
+            "{}/{}",
+            self.base_uri.trim_end_matches('/'),
+            file_name
+        ))
+    }
+}
+
+/// `Key::MetadataV1`, the first byte of every Metaplex metadata account.
+const METAPLEX_METADATA_KEY: u8 = 4;
+/// Token-2022 pads extended mints to the token account length, then writes
+/// one account type byte before the extension entries.
+const TOKEN_2022_EXTENSIONS_OFFSET: usize = 166;
+/// Extension type of Token-2022's embedded `TokenMetadata`.
+const TOKEN_METADATA_EXTENSION: u16 = 19;
+/// Off-chain JSON documents fetched at once by [`fetch_token_metadatas`].
+const JSON_FETCH_CONCURRENCY: usize = 16;
+/// How long [`fetch_token_metadatas`] waits for one JSON document. URIs are
+/// chosen by token creators, so a slow host must not stall a whole batch.
+const JSON_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Largest JSON document [`fetch_metadata_json`] reads before giving up.
+pub const MAX_METADATA_JSON_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+/// Leading fields of a Metaplex metadata account, which is all we read.
+#[derive(BorshDeserialize)]
+struct MetaplexMetadata {
+    key: u8,
+    update_authority: Pubkey,
+    mint: Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+    _seller_fee_basis_points: u16,
+    creators: Option<Vec<Creator>>,
+}
+
+/// Token-2022's `TokenMetadata` extension.
+#[derive(BorshDeserialize)]
+struct Token2022Metadata {
+    update_authority: Pubkey,
+    mint: Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+}
+
+/// A mint's on-chain metadata, read from its Metaplex metadata account or,
+/// for Token-2022 mints, from the mint's own metadata extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MintMetadata {
+    pub mint: Pubkey,
+    pub update_authority: Option<Pubkey>,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    /// Always empty for Token-2022 metadata, which has no creators.
+    pub creators: Vec<Creator>,
+    /// The document at `uri`, when it was requested and could be read.
+    pub json: Option<TokenMetadata>,
+}
+
+impl MintMetadata {
+    /// Decodes a Metaplex metadata account, stripping the NUL padding
+    /// Metaplex stores its strings with.
+    pub fn from_metaplex_account(data: &[u8]) -> Result<Self, ErrorCode> {
+        let metadata = MetaplexMetadata::deserialize(&mut &data[..])
+            .map_err(|_| ErrorCode::DeserializationError)?;
+        if metadata.key != METAPLEX_METADATA_KEY {
+            return Err(ErrorCode::DeserializationError);
+        }
+        Ok(Self {
+            mint: metadata.mint,
+            update_authority: Some(metadata.update_authority),
+            name: trim_padding(metadata.name),
+            symbol: trim_padding(metadata.symbol),
+            uri: trim_padding(metadata.uri),
+            creators: metadata.creators.unwrap_or_default(),
+            json: None,
+        })
+    }
+
+    /// Decodes the metadata extension of a Token-2022 mint account.
+    pub fn from_token_2022_mint(data: &[u8]) -> Result<Self, ErrorCode> {
+        let mut rest = data
+            .get(TOKEN_2022_EXTENSIONS_OFFSET..)
+            .ok_or(ErrorCode::MetadataNotFound)?;
+        while rest.len() >= 4 {
+            let extension = u16::from_le_bytes([rest[0], rest[1]]);
+            let len = u16::from_le_bytes([rest[2], rest[3]]) as usize;
+            let value = rest
+                .get(4..4 + len)
+                .ok_or(ErrorCode::DeserializationError)?;
+            if extension == TOKEN_METADATA_EXTENSION {
+                let metadata = Token2022Metadata::deserialize(&mut &value[..])
+                    .map_err(|_| ErrorCode::DeserializationError)?;
+                return Ok(Self {
+                    mint: metadata.mint,
+                    update_authority: (metadata.update_authority != Pubkey::default())
+                        .then_some(metadata.update_authority),
+                    name: metadata.name,
+                    symbol: metadata.symbol,
+                    uri: metadata.uri,
+                    creators: vec![],
+                    json: None,
+                });
+            }
+            rest = &rest[4 + len..];
+        }
+        Err(ErrorCode::MetadataNotFound)
+    }
+
+    fn from_accounts(
+        metadata_account: Option<&Account>,
+        mint_account: Option<&Account>,
+    ) -> Result<Option<Self>, ErrorCode> {
+        if let Some(account) = metadata_account {
+            return Self::from_metaplex_account(&account.data).map(Some);
+        }
+        match mint_account {
+            Some(account) if account.owner == TOKEN_2022_PROGRAM => {
+                match Self::from_token_2022_mint(&account.data) {
+                    Err(ErrorCode::MetadataNotFound) => Ok(None),
+                    result => result.map(Some),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+fn trim_padding(value: String) -> String {
+    value.trim_end_matches('\0').to_string()
+}
+
+/// Fetches and parses the off-chain JSON a metadata `uri` points to.
+/// Documents larger than [`MAX_METADATA_JSON_BYTES`] are rejected.
+pub fn fetch_metadata_json(
+    http: &reqwest::blocking::Client,
+    uri: &str,
+) -> Result<TokenMetadata, ErrorCode> {
+    let response = http
+        .get(uri)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|_| ErrorCode::MetadataJsonFetchFailed)?;
+    let mut body = vec![];
+    response
+        .take(MAX_METADATA_JSON_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|_| ErrorCode::MetadataJsonFetchFailed)?;
+    if body.len() as u64 > MAX_METADATA_JSON_BYTES {
+        return Err(ErrorCode::MetadataJsonFetchFailed);
+    }
+    serde_json::from_slice(&body).map_err(|_| ErrorCode::MetadataJsonFetchFailed)
+}
+
+/// Fetches `mint`'s on-chain metadata, and its off-chain JSON as well when
+/// `fetch_json` is set. A JSON document that cannot be read leaves `json`
+/// empty rather than failing the call.
+pub fn fetch_token_metadata(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    mint: &Pubkey,
+    fetch_json: bool,
+) -> Result<MintMetadata, ErrorCode> {
+    fetch_token_metadatas(rpc_client, config, std::slice::from_ref(mint), fetch_json)
+        .remove(mint)
+        .unwrap_or(Err(ErrorCode::AccountFetchFailed))?
+        .ok_or(ErrorCode::MetadataNotFound)
+}
+
+/// Batched [`fetch_token_metadata`] for screeners. Each mint maps to
+/// `Ok(None)` when it has no metadata, or to its own error; the off-chain
+/// documents are fetched concurrently, each with a timeout and a size cap.
+pub fn fetch_token_metadatas(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    mints: &[Pubkey],
+    fetch_json: bool,
+) -> HashMap<Pubkey, Result<Option<MintMetadata>, ErrorCode>> {
+    // Metadata PDAs first, then the mints themselves for Token-2022 metadata.
+    let addresses: Vec<Pubkey> = mints
+        .iter()
+        .map(|mint| get_metadata_pda(config, mint))
+        .chain(mints.iter().copied())
+        .collect();
+    let accounts = fetch_multiple_accounts(rpc_client, &addresses);
+    let (metadata_accounts, mint_accounts) = accounts.split_at(mints.len());
+
+    let mut results: Vec<Result<Option<MintMetadata>, ErrorCode>> = metadata_accounts
+        .iter()
+        .zip(mint_accounts)
+        .map(|(metadata_account, mint_account)| {
+            let metadata_account = metadata_account.clone()?;
+            let mint_account = mint_account.clone()?;
+            MintMetadata::from_accounts(metadata_account.as_ref(), mint_account.as_ref())
+        })
+        .collect();
+
+    if fetch_json {
+        let http = reqwest::blocking::Client::builder()
+            .connect_timeout(HTTP_CONNECT_TIMEOUT)
+            .timeout(JSON_FETCH_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        for chunk in results.chunks_mut(JSON_FETCH_CONCURRENCY) {
+            thread::scope(|scope| {
+                for metadata in chunk.iter_mut().filter_map(|result| match result {
+                    Ok(Some(metadata)) if !metadata.uri.is_empty() => Some(metadata),
+                    _ => None,
+                }) {
+                    let http = &http;
+                    scope.spawn(move || {
+                        metadata.json = fetch_metadata_json(http, &metadata.uri).ok();
+                    });
+                }
+            });
+        }
+    }
+
+    mints.iter().copied().zip(results).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_builder_enforces_metaplex_limits() {
+        assert!(matches!(
+            TokenMetadata::builder("", "TEST").build(),
+            Err(ErrorCode::InvalidMetadata)
+        ));
+        assert!(matches!(
+            TokenMetadata::builder("Test Token", "TOOLONGSYMBOL").build(),
+            Err(ErrorCode::InvalidMetadata)
+        ));
+        assert!(matches!(
+            TokenMetadata::builder("x".repeat(MAX_NAME_LENGTH + 1), "TEST").build(),
+            Err(ErrorCode::InvalidMetadata)
+        ));
+        assert!(matches!(
+            TokenMetadata::builder("Test Token", "TEST")
+                .image(format!("https://{}", "a".repeat(MAX_URI_LENGTH)))
+                .build(),
+            Err(ErrorCode::InvalidMetadata)
+        ));
+
+        let metadata = TokenMetadata::builder("Test Token", "TEST")
+            .website("https://example.com")
+            .build()
+            .unwrap();
+        let json: Value = serde_json::from_slice(&metadata.to_json().unwrap()).unwrap();
+        assert_eq!(json["website"], "https://example.com");
+        assert!(json.get("twitter").is_none());
+    }
+
+    #[test]
+    fn test_upload_stores_image_then_json() {
+        let dir = std::env::temp_dir().join(format!("token-metadata-{}", Pubkey::new_unique()));
+        let backend = LocalUploadBackend::new(&dir, "https://cdn.example.com/tokens");
+        let image = TokenImage {
+            file_name: "logo.png".to_string(),
+            content_type: "image/png".to_string(),
+            bytes: vec![0x89, b'P', b'N', b'G'],
+        };
+
+        let uri = TokenMetadata::builder("Test Token", "TEST")
+            .description("A test token")
+            .build()
+            .unwrap()
+            .upload(&backend, Some(&image))
+            .unwrap();
+
+        let json_name = uri.strip_prefix("https://cdn.example.com/tokens/").unwrap();
+        let json = fs::read(dir.join(json_name)).unwrap();
+        assert_eq!(json_name, content_file_name(&json, Some("json")));
+        let stored: TokenMetadata = serde_json::from_slice(&json).unwrap();
+        let image_name = content_file_name(&image.bytes, Some("png"));
+        assert_eq!(
+            stored.image,
+            Some(format!("https://cdn.example.com/tokens/{image_name}"))
+        );
+        assert_eq!(fs::read(dir.join(image_name)).unwrap(), image.bytes);
+
+        for file_name in ["../escape.json", "nested/test.json", "..", ""] {
+            assert!(matches!(
+                backend.upload(file_name, "application/json", b"{}"),
+                Err(ErrorCode::InvalidMetadata)
+            ));
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Serves `body` to a single GET and returns its URL.
+    fn serve_once(body: Vec<u8>) -> String {
+        use std::{
+            io::{BufRead, BufReader, Write},
+            net::TcpListener,
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/token.json", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while line != "\r\n" {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+            }
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(&body);
+        });
+        url
+    }
+
+    #[test]
+    fn test_fetch_metadata_json_caps_the_body() {
+        let http = reqwest::blocking::Client::new();
+        let metadata = TokenMetadata::builder("Test Token", "TEST")
+            .build()
+            .unwrap();
+
+        let url = serve_once(metadata.to_json().unwrap());
+        assert_eq!(fetch_metadata_json(&http, &url).unwrap(), metadata);
+
+        let mut oversized = metadata;
+        oversized.description = "x".repeat(MAX_METADATA_JSON_BYTES as usize);
+        let url = serve_once(oversized.to_json().unwrap());
+        assert!(matches!(
+            fetch_metadata_json(&http, &url),
+            Err(ErrorCode::MetadataJsonFetchFailed)
+        ));
+    }
+
+    fn borsh_string(value: &str, padded_len: usize) -> Vec<u8> {
+        let mut padded = value.as_bytes().to_vec();
+        padded.resize(padded_len.max(value.len()), 0);
+        let mut bytes = (padded.len() as u32).to_le_bytes().to_vec();
+        bytes.extend(padded);
+        bytes
+    }
+
+    #[test]
+    fn test_decode_metaplex_and_token_2022_metadata() {
+        let mint = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+
+        let mut metaplex = vec![METAPLEX_METADATA_KEY];
+        metaplex.extend(authority.to_bytes());
+        metaplex.extend(mint.to_bytes());
+        metaplex.extend(borsh_string("Test Token", MAX_NAME_LENGTH));
+        metaplex.extend(borsh_string("TEST", MAX_SYMBOL_LENGTH));
+        metaplex.extend(borsh_string(
+            "https://example.com/test.json",
+            MAX_URI_LENGTH,
+        ));
+        metaplex.extend(0u16.to_le_bytes());
+        metaplex.extend([1, 1, 0, 0, 0]); // Some(vec![..]) with one creator
+        metaplex.extend(creator.to_bytes());
+        metaplex.extend([1, 100]);
+        metaplex.extend([0; 16]); // trailing fields we do not read
+
+        let decoded = MintMetadata::from_metaplex_account(&metaplex).unwrap();
+        assert_eq!(decoded.mint, mint);
+        assert_eq!(decoded.update_authority, Some(authority));
+        assert_eq!(decoded.name, "Test Token");
+        assert_eq!(decoded.symbol, "TEST");
+        assert_eq!(decoded.uri, "https://example.com/test.json");
+        assert_eq!(
+            decoded.creators,
+            vec![Creator {
+                address: creator,
+                verified: true,
+                share: 100,
+            }]
+        );
+
+        let mut extension = Pubkey::default().to_bytes().to_vec();
+        extension.extend(mint.to_bytes());
+        extension.extend(borsh_string("Test Token", 0));
+        extension.extend(borsh_string("TEST", 0));
+        extension.extend(borsh_string("https://example.com/test.json", 0));
+        extension.extend(0u32.to_le_bytes());
+
+        let mut mint_account = vec![0; TOKEN_2022_EXTENSIONS_OFFSET];
+        // A metadata pointer entry ahead of the metadata itself.
+        mint_account.extend(18u16.to_le_bytes());
+        mint_account.extend(64u16.to_le_bytes());
+        mint_account.extend([0; 64]);
+        mint_account.extend(TOKEN_METADATA_EXTENSION.to_le_bytes());
+        mint_account.extend((extension.len() as u16).to_le_bytes());
+        mint_account.extend(extension);
+
+        let decoded = MintMetadata::from_token_2022_mint(&mint_account).unwrap();
+        assert_eq!(decoded.mint, mint);
+        assert_eq!(decoded.update_authority, None);
+        assert_eq!(decoded.symbol, "TEST");
+        assert!(decoded.creators.is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+use solana_client::{nonce_utils, rpc_client::RpcClient};
+use solana_nonce::state::State;
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount, Message},
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::Transaction,
+};
+use solana_system_interface::instruction::{advance_nonce_account, create_nonce_account};
+
+use crate::{constants::SYSTEM_PROGRAM, errors::ErrorCode, lookup_table::compile_v0_message};
+
+/// Size of an initialized nonce account.
+pub const NONCE_ACCOUNT_LEN: usize = State::size();
+
+/// The parts of a nonce account needed to build a durable transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceInfo {
+    pub address: Pubkey,
+    /// Signer of the `advance_nonce_account` instruction.
+    pub authority: Pubkey,
+    /// Stands in for the recent blockhash until the nonce is advanced.
+    pub blockhash: Hash,
+}
+
+impl NonceInfo {
+    pub fn from_account(address: &Pubkey, account: &Account) -> Result<Self, ErrorCode> {
+        let data =
+            nonce_utils::data_from_account(account).map_err(|_| ErrorCode::InvalidNonceAccount)?;
+        Ok(Self {
+            address: *address,
+            authority: data.authority,
+            blockhash: data.blockhash(),
+        })
+    }
+}
+
+/// Instructions creating and initializing `nonce` with `authority`, funded
+/// with `lamports` from `payer`. `nonce` must sign the transaction.
+pub fn create_nonce_account_ixs(
+    payer: &Pubkey,
+    nonce: &Pubkey,
+    authority: &Pubkey,
+    lamports: u64,
+) -> Vec<Instruction> {
+    create_nonce_account(payer, nonce, authority, lamports)
+}
+
+pub fn fetch_nonce(rpc_client: &RpcClient, address: &Pubkey) -> Result<NonceInfo, ErrorCode> {
+    let account = rpc_client
+        .get_account(address)
+        .map_err(|_| ErrorCode::NonceAccountNotFound)?;
+    NonceInfo::from_account(address, &account)
+}
+
+/// Prepends the `advance_nonce_account` instruction, which the runtime
+/// requires to come first in a durable transaction.
+pub fn with_advance_nonce(nonce: &NonceInfo, instructions: &[Instruction]) -> Vec<Instruction> {
+    let mut durable = vec![advance_nonce_account(&nonce.address, &nonce.authority)];
+    durable.extend_from_slice(instructions);
+    durable
+}
+
+/// Builds a legacy message that stays valid until `nonce` is advanced.
+pub fn durable_message(payer: &Pubkey, nonce: &NonceInfo, instructions: &[Instruction]) -> Message {
+    Message::new_with_blockhash(
+        &with_advance_nonce(nonce, instructions),
+        Some(payer),
+        &nonce.blockhash,
+    )
+}
+
+/// Builds a v0 message that stays valid until `nonce` is advanced.
+pub fn durable_v0_message(
+    payer: &Pubkey,
+    nonce: &NonceInfo,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Result<v0::Message, ErrorCode> {
+    compile_v0_message(
+        payer,
+        &with_advance_nonce(nonce, instructions),
+        lookup_tables,
+        nonce.blockhash,
+    )
+}
+
+fn advances_nonce(message: &Message, nonce: &Pubkey) -> bool {
+    message.instructions.first().is_some_and(|instruction| {
+        message
+            .account_keys
+            .get(instruction.program_id_index as usize)
+            == Some(&SYSTEM_PROGRAM)
+            && instruction
+                .accounts
+                .first()
+                .and_then(|index| message.account_keys.get(*index as usize))
+                == Some(nonce)
+            && instruction.data == advance_nonce_account(nonce, nonce).data
+    })
+}
+
+/// Moves a pre-signed durable transaction onto the current value of `nonce`
+/// and signs it again with `signers`. Signatures from signers not passed in
+/// are cleared and must be added again before sending.
+pub fn refresh_durable_transaction(
+    transaction: &Transaction,
+    nonce: &NonceInfo,
+    signers: &[&dyn Signer],
+) -> Result<Transaction, ErrorCode> {
+    if !advances_nonce(&transaction.message, &nonce.address) {
+        return Err(ErrorCode::NonceNotAdvanced);
+    }
+    let mut refreshed = transaction.clone();
+    refreshed
+        .try_partial_sign(signers, nonce.blockhash)
+        .map_err(|_| ErrorCode::SigningFailed)?;
+    Ok(refreshed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_nonce::{state::DurableNonce, versions::Versions};
+    use solana_sdk::{signature::Keypair, system_instruction::transfer};
+
+    fn nonce_account(authority: &Pubkey, blockhash: &Hash) -> Account {
+        let state =
+            State::new_initialized(authority, DurableNonce::from_blockhash(blockhash), 5_000);
+        Account {
+            lamports: 1_447_680,
+            data: bincode::serialize(&Versions::new(state)).unwrap(),
+            owner: SYSTEM_PROGRAM,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_refresh_durable_transaction_follows_the_nonce() {
+        let payer = Keypair::new();
+        let address = Pubkey::new_unique();
+        let first = NonceInfo::from_account(
+            &address,
+            &nonce_account(&payer.pubkey(), &Hash::new_unique()),
+        )
+        .unwrap();
+        assert_eq!(first.authority, payer.pubkey());
+
+        let transfer_ix = transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+        let message = durable_message(&payer.pubkey(), &first, &[transfer_ix]);
+        let signed = Transaction::new(&[&payer], message, first.blockhash);
+        assert!(signed.verify().is_ok());
+
+        let advanced = NonceInfo::from_account(
+            &address,
+            &nonce_account(&payer.pubkey(), &Hash::new_unique()),
+        )
+        .unwrap();
+        let refreshed = refresh_durable_transaction(&signed, &advanced, &[&payer]).unwrap();
+        assert_eq!(refreshed.message.recent_blockhash, advanced.blockhash);
+        assert!(refreshed.verify().is_ok());
+
+        let other = NonceInfo {
+            address: Pubkey::new_unique(),
+            ..advanced
+        };
+        assert!(matches!(
+            refresh_durable_transaction(&signed, &other, &[&payer]),
+            Err(ErrorCode::NonceNotAdvanced)
+        ));
+    }
+}
@@ -0,0 +1,179 @@
+use std::str::FromStr;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, message::Message, pubkey::Pubkey, signature::Signature,
+    signer::Signer, transaction::Transaction,
+};
+
+use crate::errors::ErrorCode;
+
+/// An unsigned message handed to an external signer, such as an HSM service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningRequest {
+    /// The serialized legacy message, base64 encoded. Signers sign these bytes.
+    pub message: String,
+    /// Base58 addresses that must sign, fee payer first.
+    pub signers: Vec<String>,
+}
+
+/// A signature produced for a [`SigningRequest`], detached from the transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetachedSignature {
+    /// Base58 address of the signer.
+    pub signer: String,
+    /// Base58 signature over the message bytes.
+    pub signature: String,
+}
+
+/// What a signer sends back: one signature per key it holds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningResponse {
+    pub signatures: Vec<DetachedSignature>,
+}
+
+impl SigningRequest {
+    pub fn new(message: &Message) -> Self {
+        let required = message.header.num_required_signatures as usize;
+        Self {
+            message: BASE64_STANDARD.encode(message.serialize()),
+            signers: message.account_keys[..required]
+                .iter()
+                .map(Pubkey::to_string)
+                .collect(),
+        }
+    }
+
+    /// Builds the request for `instructions` paid by `payer`. For a create,
+    /// the signers are the user and the mint; for a buy or sell, the user.
+    pub fn from_instructions(
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        recent_blockhash: Hash,
+    ) -> Self {
+        Self::new(&Message::new_with_blockhash(
+            instructions,
+            Some(payer),
+            &recent_blockhash,
+        ))
+    }
+
+    pub fn message_bytes(&self) -> Result<Vec<u8>, ErrorCode> {
+        BASE64_STANDARD
+            .decode(&self.message)
+            .map_err(|_| ErrorCode::DeserializationError)
+    }
+
+    pub fn message(&self) -> Result<Message, ErrorCode> {
+        bincode::deserialize(&self.message_bytes()?).map_err(|_| ErrorCode::DeserializationError)
+    }
+
+    /// Signs the request with every key in `signers` that it asks for. This
+    /// is what a remote signer does; locally it stands in for one.
+    pub fn sign(&self, signers: &[&dyn Signer]) -> Result<SigningResponse, ErrorCode> {
+        let bytes = self.message_bytes()?;
+        let mut response = SigningResponse::default();
+        for signer in signers {
+            let pubkey = signer.pubkey().to_string();
+            if !self.signers.contains(&pubkey) {
+                continue;
+            }
+            let signature = signer
+                .try_sign_message(&bytes)
+                .map_err(|_| ErrorCode::SigningFailed)?;
+            response.signatures.push(DetachedSignature {
+                signer: pubkey,
+                signature: signature.to_string(),
+            });
+        }
+        Ok(response)
+    }
+
+    /// Places the detached signatures from `responses` in the transaction
+    /// and checks that every required signer signed and that all signatures
+    /// verify, so the result is ready to broadcast.
+    pub fn assemble(&self, responses: &[SigningResponse]) -> Result<Transaction, ErrorCode> {
+        let message = self.message()?;
+        let mut transaction = Transaction::new_unsigned(message);
+
+        for detached in responses.iter().flat_map(|response| &response.signatures) {
+            let signer =
+                Pubkey::from_str(&detached.signer).map_err(|_| ErrorCode::DeserializationError)?;
+            let signature = Signature::from_str(&detached.signature)
+                .map_err(|_| ErrorCode::DeserializationError)?;
+            let position = transaction
+                .get_signing_keypair_positions(&[signer])
+                .map_err(|_| ErrorCode::InvalidSignature)?[0]
+                .ok_or(ErrorCode::InvalidSignature)?;
+            transaction.signatures[position] = signature;
+        }
+
+        if !transaction.is_signed() {
+            return Err(ErrorCode::MissingSignature);
+        }
+        transaction
+            .verify()
+            .map_err(|_| ErrorCode::InvalidSignature)?;
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::PumpConfig,
+        instructions::create::{create_ix, CreateAccounts, CreateArgs},
+    };
+    use solana_sdk::signature::Keypair;
+
+    #[test]
+    fn test_round_trip_through_a_stand_in_signer() {
+        let user = Keypair::new();
+        let mint = Keypair::new();
+        let create = create_ix(
+            &PumpConfig::default(),
+            CreateAccounts {
+                mint: mint.pubkey(),
+                user: user.pubkey(),
+            },
+            CreateArgs {
+                name: "Offline".to_string(),
+                symbol: "OFF".to_string(),
+                uri: "https://example.com/metadata.json".to_string(),
+                creator: user.pubkey(),
+            },
+        );
+        let request =
+            SigningRequest::from_instructions(&user.pubkey(), &[create], Hash::new_unique());
+        assert_eq!(
+            request.signers,
+            vec![user.pubkey().to_string(), mint.pubkey().to_string()]
+        );
+
+        // The request and responses cross the wire as JSON.
+        let request: SigningRequest =
+            serde_json::from_str(&serde_json::to_string(&request).unwrap()).unwrap();
+        let user_response = request.sign(&[&user]).unwrap();
+        assert!(matches!(
+            request.assemble(std::slice::from_ref(&user_response)),
+            Err(ErrorCode::MissingSignature)
+        ));
+
+        let mint_response: SigningResponse =
+            serde_json::from_str(&serde_json::to_string(&request.sign(&[&mint]).unwrap()).unwrap())
+                .unwrap();
+        let transaction = request
+            .assemble(&[user_response.clone(), mint_response])
+            .unwrap();
+        assert!(transaction.verify().is_ok());
+
+        let mut forged = request.sign(&[&Keypair::new(), &mint]).unwrap();
+        forged.signatures[0].signature = Signature::from([7; 64]).to_string();
+        assert!(matches!(
+            request.assemble(&[user_response, forged]),
+            Err(ErrorCode::InvalidSignature)
+        ));
+    }
+}
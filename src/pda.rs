@@ -1,7 +1,14 @@
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+};
+
 use solana_sdk::pubkey::Pubkey;
-use spl_associated_token_account::get_associated_token_address;
 
-use crate::{constants::MPL_TOKEN_METADATA, PUMP_DOT_FUN_PROGRAM_ID};
+use crate::{
+    config::PumpConfig,
+    constants::{ASSOCIATED_TOKEN_PROGRAM, MAYHEM_PROGRAM, TOKEN_2022_PROGRAM},
+};
 
 const BONDING_CURVE_SEED: &[u8] = b"bonding-curve";
 const METADATA_SEED: &[u8] = b"metadata";
@@ -12,43 +19,316 @@ const MINT_AUTHORITY_SEED: &[u8] = b"mint-authority";
 
 const CREATOR_VAULT_SEED: &[u8] = b"creator-vault";
 
-pub fn get_bonding_curve_pda(mint: &Pubkey) -> Pubkey {
-    Pubkey::find_program_address(
-        &[BONDING_CURVE_SEED, mint.to_bytes().as_slice()],
-        &PUMP_DOT_FUN_PROGRAM_ID,
+const MAYHEM_GLOBAL_PARAMS_SEED: &[u8] = b"global-params";
+const MAYHEM_SOL_VAULT_SEED: &[u8] = b"sol-vault";
+const MAYHEM_STATE_SEED: &[u8] = b"mayhem-state";
+
+/// Entries the shared cache holds before it starts over, so a long-running
+/// process scanning every launch does not grow without bound.
+const SHARED_CACHE_CAPACITY: usize = 65_536;
+
+/// What a cached PDA was derived from. Every key carries the program it was
+/// derived under so configs for different deployments never share entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PdaKey {
+    Global(Pubkey),
+    MintAuthority(Pubkey),
+    BondingCurve(Pubkey, Pubkey),
+    AssociatedBondingCurve(Pubkey, Pubkey, Pubkey),
+    CreatorVault(Pubkey, Pubkey),
+}
+
+/// Thread-safe memo of `find_program_address` results, keyed by program and
+/// by mint or creator.
+///
+/// The `get_*` functions in this module go through [`PdaCache::shared`], so
+/// instruction builders only pay for a derivation the first time they see a
+/// mint. A separate cache is only needed to control its capacity.
+#[derive(Debug)]
+pub struct PdaCache {
+    entries: RwLock<HashMap<PdaKey, (Pubkey, u8)>>,
+    capacity: usize,
+}
+
+impl PdaCache {
+    /// Creates an empty cache that is cleared once it holds `capacity`
+    /// entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// The process-wide cache behind the `get_*` functions.
+    pub fn shared() -> &'static PdaCache {
+        static SHARED: OnceLock<PdaCache> = OnceLock::new();
+        SHARED.get_or_init(|| PdaCache::new(SHARED_CACHE_CAPACITY))
+    }
+
+    pub fn global(&self, config: &PumpConfig) -> (Pubkey, u8) {
+        self.get_or_derive(PdaKey::Global(config.program_id), || {
+            Pubkey::find_program_address(&[GLOBAL_SEED], &config.program_id)
+        })
+    }
+
+    pub fn mint_authority(&self, config: &PumpConfig) -> (Pubkey, u8) {
+        self.get_or_derive(PdaKey::MintAuthority(config.program_id), || {
+            Pubkey::find_program_address(&[MINT_AUTHORITY_SEED], &config.program_id)
+        })
+    }
+
+    pub fn bonding_curve(&self, config: &PumpConfig, mint: &Pubkey) -> (Pubkey, u8) {
+        self.get_or_derive(PdaKey::BondingCurve(config.program_id, *mint), || {
+            Pubkey::find_program_address(
+                &[BONDING_CURVE_SEED, mint.to_bytes().as_slice()],
+                &config.program_id,
+            )
+        })
+    }
+
+    /// The bonding curve's token account under `token_program`, derived the
+    /// same way as `get_associated_token_address_with_program_id`.
+    pub fn associated_bonding_curve(
+        &self,
+        config: &PumpConfig,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> (Pubkey, u8) {
+        let key = PdaKey::AssociatedBondingCurve(config.program_id, *mint, *token_program);
+        self.get_or_derive(key, || {
+            let bonding_curve = self.bonding_curve(config, mint).0;
+            Pubkey::find_program_address(
+                &[
+                    bonding_curve.as_ref(),
+                    token_program.as_ref(),
+                    mint.as_ref(),
+                ],
+                &ASSOCIATED_TOKEN_PROGRAM,
+            )
+        })
+    }
+
+    pub fn creator_vault(&self, config: &PumpConfig, creator: &Pubkey) -> (Pubkey, u8) {
+        self.get_or_derive(PdaKey::CreatorVault(config.program_id, *creator), || {
+            Pubkey::find_program_address(
+                &[CREATOR_VAULT_SEED, creator.to_bytes().as_slice()],
+                &config.program_id,
+            )
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    fn get_or_derive(&self, key: PdaKey, derive: impl FnOnce() -> (Pubkey, u8)) -> (Pubkey, u8) {
+        if let Some(found) = self
+            .entries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+        {
+            return *found;
+        }
+
+        // Derive without holding the lock; a racing thread computes the same
+        // value, so whichever insert lands last is still correct.
+        let derived = derive();
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity {
+            entries.clear();
+        }
+        entries.insert(key, derived);
+        derived
+    }
+}
+
+pub fn get_bonding_curve_pda(config: &PumpConfig, mint: &Pubkey) -> Pubkey {
+    get_bonding_curve_pda_with_bump(config, mint).0
+}
+
+pub fn get_bonding_curve_pda_with_bump(config: &PumpConfig, mint: &Pubkey) -> (Pubkey, u8) {
+    PdaCache::shared().bonding_curve(config, mint)
+}
+
+/// Rebuilds the bonding curve address from a bump the caller already knows,
+/// skipping the bump search. `None` if `bump` does not produce a valid PDA.
+pub fn get_bonding_curve_pda_from_bump(
+    config: &PumpConfig,
+    mint: &Pubkey,
+    bump: u8,
+) -> Option<Pubkey> {
+    Pubkey::create_program_address(
+        &[BONDING_CURVE_SEED, mint.to_bytes().as_slice(), &[bump]],
+        &config.program_id,
     )
-    .0
+    .ok()
+}
+
+pub fn get_mint_authority_pda(config: &PumpConfig) -> Pubkey {
+    get_mint_authority_pda_with_bump(config).0
+}
+
+pub fn get_mint_authority_pda_with_bump(config: &PumpConfig) -> (Pubkey, u8) {
+    PdaCache::shared().mint_authority(config)
+}
+
+pub fn get_mint_authority_pda_from_bump(config: &PumpConfig, bump: u8) -> Option<Pubkey> {
+    Pubkey::create_program_address(&[MINT_AUTHORITY_SEED, &[bump]], &config.program_id).ok()
 }
 
-pub fn get_mint_authority_pda() -> Pubkey {
-    Pubkey::find_program_address(&[MINT_AUTHORITY_SEED], &PUMP_DOT_FUN_PROGRAM_ID).0
+pub fn get_associated_bonding_curve(
+    config: &PumpConfig,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    PdaCache::shared()
+        .associated_bonding_curve(config, mint, token_program)
+        .0
 }
 
-pub fn get_associated_bonding_curve(mint: &Pubkey) -> Pubkey {
-    let bonding_curve = get_bonding_curve_pda(mint);
-    get_associated_token_address(&bonding_curve, mint)
+pub fn get_mayhem_global_params_pda() -> Pubkey {
+    Pubkey::find_program_address(&[MAYHEM_GLOBAL_PARAMS_SEED], &MAYHEM_PROGRAM).0
 }
 
-pub fn get_metadata_pda(mint: &Pubkey) -> Pubkey {
+pub fn get_mayhem_sol_vault_pda() -> Pubkey {
+    Pubkey::find_program_address(&[MAYHEM_SOL_VAULT_SEED], &MAYHEM_PROGRAM).0
+}
+
+pub fn get_mayhem_state_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[MAYHEM_STATE_SEED, mint.as_ref()], &MAYHEM_PROGRAM).0
+}
+
+/// The mayhem SOL vault's Token-2022 account for `mint`.
+pub fn get_mayhem_token_vault(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            get_mayhem_sol_vault_pda().as_ref(),
+            TOKEN_2022_PROGRAM.as_ref(),
+            mint.as_ref(),
+        ],
+        &ASSOCIATED_TOKEN_PROGRAM,
+    )
+    .0
+}
+
+pub fn get_metadata_pda(config: &PumpConfig, mint: &Pubkey) -> Pubkey {
     Pubkey::find_program_address(
         &[
             METADATA_SEED,
-            MPL_TOKEN_METADATA.to_bytes().as_slice(),
+            config.metadata_program.to_bytes().as_slice(),
             mint.to_bytes().as_slice(),
         ],
-        &MPL_TOKEN_METADATA,
+        &config.metadata_program,
     )
     .0
 }
 
-pub fn get_global_pda() -> Pubkey {
-    Pubkey::find_program_address(&[GLOBAL_SEED], &PUMP_DOT_FUN_PROGRAM_ID).0
+pub fn get_global_pda(config: &PumpConfig) -> Pubkey {
+    get_global_pda_with_bump(config).0
 }
 
-pub fn get_creator_vault_pda(creator: &Pubkey) -> Pubkey {
-    Pubkey::find_program_address(
-        &[CREATOR_VAULT_SEED, creator.to_bytes().as_slice()],
-        &PUMP_DOT_FUN_PROGRAM_ID,
+pub fn get_global_pda_with_bump(config: &PumpConfig) -> (Pubkey, u8) {
+    PdaCache::shared().global(config)
+}
+
+pub fn get_global_pda_from_bump(config: &PumpConfig, bump: u8) -> Option<Pubkey> {
+    Pubkey::create_program_address(&[GLOBAL_SEED, &[bump]], &config.program_id).ok()
+}
+
+pub fn get_creator_vault_pda(config: &PumpConfig, creator: &Pubkey) -> Pubkey {
+    get_creator_vault_pda_with_bump(config, creator).0
+}
+
+pub fn get_creator_vault_pda_with_bump(config: &PumpConfig, creator: &Pubkey) -> (Pubkey, u8) {
+    PdaCache::shared().creator_vault(config, creator)
+}
+
+pub fn get_creator_vault_pda_from_bump(
+    config: &PumpConfig,
+    creator: &Pubkey,
+    bump: u8,
+) -> Option<Pubkey> {
+    Pubkey::create_program_address(
+        &[CREATOR_VAULT_SEED, creator.to_bytes().as_slice(), &[bump]],
+        &config.program_id,
     )
-    .0
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{TOKEN_2022_PROGRAM, TOKEN_PROGRAM};
+    use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+    #[test]
+    fn test_cached_pdas_match_direct_derivation() {
+        let config = PumpConfig::default();
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+
+        let (curve, curve_bump) = get_bonding_curve_pda_with_bump(&config, &mint);
+        assert_eq!(
+            (curve, curve_bump),
+            Pubkey::find_program_address(&[BONDING_CURVE_SEED, mint.as_ref()], &config.program_id)
+        );
+        assert_eq!(
+            get_bonding_curve_pda_from_bump(&config, &mint, curve_bump),
+            Some(curve)
+        );
+        for token_program in [TOKEN_PROGRAM, TOKEN_2022_PROGRAM] {
+            assert_eq!(
+                get_associated_bonding_curve(&config, &mint, &token_program),
+                get_associated_token_address_with_program_id(&curve, &mint, &token_program)
+            );
+        }
+
+        let (global, global_bump) = get_global_pda_with_bump(&config);
+        assert_eq!(get_global_pda_from_bump(&config, global_bump), Some(global));
+        let (authority, authority_bump) = get_mint_authority_pda_with_bump(&config);
+        assert_eq!(
+            get_mint_authority_pda_from_bump(&config, authority_bump),
+            Some(authority)
+        );
+        let (vault, vault_bump) = get_creator_vault_pda_with_bump(&config, &creator);
+        assert_eq!(
+            get_creator_vault_pda_from_bump(&config, &creator, vault_bump),
+            Some(vault)
+        );
+
+        // Another deployment must not be served the mainnet entries.
+        let local = config.with_program_id(Pubkey::new_unique());
+        assert_ne!(get_bonding_curve_pda(&local, &mint), curve);
+        assert_ne!(get_global_pda(&local), global);
+    }
+
+    #[test]
+    fn test_cache_starts_over_at_capacity() {
+        let config = PumpConfig::default();
+        let cache = PdaCache::new(2);
+
+        cache.bonding_curve(&config, &Pubkey::new_unique());
+        cache.creator_vault(&config, &Pubkey::new_unique());
+        assert_eq!(cache.len(), 2);
+
+        cache.global(&config);
+        cache.global(&config);
+        assert_eq!(cache.len(), 1);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
 }
@@ -0,0 +1,393 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    str::FromStr,
+    thread,
+};
+
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signature};
+
+use crate::{
+    config::PumpConfig,
+    constants::TOKEN_DECIMALS,
+    errors::ErrorCode,
+    events::{fetch_transaction_events, TradeEvent},
+    fetch::{fetch_bonding_curves, fetch_global},
+    states::{BondingCurve, Global},
+};
+
+const SIGNATURE_PAGE_LIMIT: usize = 1000;
+/// Transactions fetched at once while reading a wallet's history.
+const TRANSACTION_FETCH_CONCURRENCY: usize = 16;
+
+/// How the cost of sold tokens is taken out of the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CostBasisMethod {
+    /// Sells consume the oldest buys first.
+    #[default]
+    Fifo,
+    /// Sells take the position's running average cost.
+    AverageCost,
+}
+
+/// Profit and loss of one wallet in one mint. Amounts are in lamports;
+/// buy costs include the protocol and creator fees, and sell proceeds are
+/// net of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MintPnl {
+    pub mint: Pubkey,
+    pub method: CostBasisMethod,
+    pub buys: usize,
+    pub sells: usize,
+    pub tokens_bought: u64,
+    pub tokens_sold: u64,
+    /// Tokens still held according to the trade history.
+    pub position: u64,
+    /// SOL paid per token over every buy, fees included.
+    pub average_entry_price_in_sol: f64,
+    /// Cost of `position` under `method`.
+    pub cost_basis: u64,
+    pub realized_pnl: i64,
+    /// `fee + creator_fee` over every trade.
+    pub fees_paid: u64,
+    /// Tokens sold beyond what the history shows being bought, e.g. tokens
+    /// received by transfer or bought before the history window. They are
+    /// counted at zero cost.
+    pub untracked_tokens_sold: u64,
+    /// What selling `position` into the curve returns after fees; `None`
+    /// until marked, or when the curve has migrated.
+    pub current_value: Option<u64>,
+    pub unrealized_pnl: Option<i64>,
+}
+
+impl MintPnl {
+    fn new(mint: Pubkey, method: CostBasisMethod) -> Self {
+        Self {
+            mint,
+            method,
+            buys: 0,
+            sells: 0,
+            tokens_bought: 0,
+            tokens_sold: 0,
+            position: 0,
+            average_entry_price_in_sol: 0.0,
+            cost_basis: 0,
+            realized_pnl: 0,
+            fees_paid: 0,
+            untracked_tokens_sold: 0,
+            current_value: None,
+            unrealized_pnl: None,
+        }
+    }
+
+    /// Values the open position against `curve`. A completed curve leaves
+    /// the position unvalued, since it only trades on the AMM from then on.
+    pub fn mark_to_curve(&mut self, curve: &BondingCurve, global: &Global) {
+        if curve.complete {
+            self.current_value = None;
+            self.unrealized_pnl = None;
+            return;
+        }
+        self.current_value = curve
+            .sell_quote(self.position, global.trade_fee_basis_points(&curve.creator))
+            .ok();
+        self.unrealized_pnl = self
+            .current_value
+            .map(|value| value as i64 - self.cost_basis as i64);
+    }
+}
+
+/// A wallet's pump.fun trades, and the signatures whose transactions could
+/// not be read. Those may hold trades that are missing from `trades`.
+#[derive(Debug, Clone, Default)]
+pub struct TradeHistory {
+    pub trades: Vec<TradeEvent>,
+    pub unreadable_signatures: Vec<Signature>,
+}
+
+/// Per-mint PnL, sorted by mint, and the signatures left out of it.
+#[derive(Debug, Clone, Default)]
+pub struct PnlReport {
+    pub mints: Vec<MintPnl>,
+    pub unreadable_signatures: Vec<Signature>,
+}
+
+/// Replays `trades`, oldest first, into per-mint PnL. Every trade is taken
+/// as the same wallet's.
+pub fn compute_pnl(trades: &[TradeEvent], method: CostBasisMethod) -> HashMap<Pubkey, MintPnl> {
+    // Open lots per mint as (tokens, cost); average cost keeps a single lot.
+    let mut lots: HashMap<Pubkey, VecDeque<(u64, u64)>> = HashMap::new();
+    let mut buy_costs: HashMap<Pubkey, u64> = HashMap::new();
+    let mut pnl: HashMap<Pubkey, MintPnl> = HashMap::new();
+
+    for trade in trades {
+        let entry = pnl
+            .entry(trade.mint)
+            .or_insert_with(|| MintPnl::new(trade.mint, method));
+        let mint_lots = lots.entry(trade.mint).or_default();
+        let fees = trade.fee + trade.creator_fee;
+        entry.fees_paid += fees;
+
+        if trade.is_buy {
+            let cost = trade.sol_amount + fees;
+            entry.buys += 1;
+            entry.tokens_bought += trade.token_amount;
+            *buy_costs.entry(trade.mint).or_default() += cost;
+            match (method, mint_lots.front_mut()) {
+                (CostBasisMethod::AverageCost, Some(lot)) => {
+                    lot.0 += trade.token_amount;
+                    lot.1 += cost;
+                }
+                _ => mint_lots.push_back((trade.token_amount, cost)),
+            }
+        } else {
+            let proceeds = trade.sol_amount.saturating_sub(fees);
+            entry.sells += 1;
+            entry.tokens_sold += trade.token_amount;
+
+            let mut remaining = trade.token_amount;
+            let mut cost_of_sold = 0u64;
+            while remaining > 0 {
+                let Some(lot) = mint_lots.front_mut() else {
+                    entry.untracked_tokens_sold += remaining;
+                    break;
+                };
+                // A buy that received no tokens leaves a lot with only a
+                // cost, which the next sell realizes in full.
+                if lot.0 == 0 {
+                    cost_of_sold += lot.1;
+                    mint_lots.pop_front();
+                    continue;
+                }
+                let taken = remaining.min(lot.0);
+                let cost = (lot.1 as u128 * taken as u128 / lot.0 as u128) as u64;
+                lot.0 -= taken;
+                lot.1 -= cost;
+                cost_of_sold += cost;
+                remaining -= taken;
+                if lot.0 == 0 {
+                    mint_lots.pop_front();
+                }
+            }
+            entry.realized_pnl += proceeds as i64 - cost_of_sold as i64;
+        }
+    }
+
+    for (mint, entry) in pnl.iter_mut() {
+        let mint_lots = &lots[mint];
+        entry.position = mint_lots.iter().map(|lot| lot.0).sum();
+        entry.cost_basis = mint_lots.iter().map(|lot| lot.1).sum();
+        if entry.tokens_bought > 0 {
+            let tokens = entry.tokens_bought as f64 / 10f64.powi(TOKEN_DECIMALS as i32);
+            let sol = buy_costs[mint] as f64 / LAMPORTS_PER_SOL as f64;
+            entry.average_entry_price_in_sol = sol / tokens;
+        }
+    }
+    pnl
+}
+
+/// Pulls up to `limit` of `user`'s most recent signatures and returns the
+/// pump.fun trades `user` made in them, oldest first. Failed transactions
+/// are skipped; AMM trades after migration are not `TradeEvent`s and do not
+/// appear. A transaction that cannot be fetched is listed in
+/// `unreadable_signatures` rather than failing the whole history.
+///
+/// Trades come from the events [`fetch_transaction_events`] attributes to
+/// the pump program, so the history is only as trustworthy as that parsing.
+pub fn fetch_trade_history(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    user: &Pubkey,
+    limit: usize,
+) -> Result<TradeHistory, ErrorCode> {
+    let mut signatures = vec![];
+    let mut before = None;
+    let mut seen = 0;
+    while seen < limit {
+        let page_limit = SIGNATURE_PAGE_LIMIT.min(limit - seen);
+        let page = rpc_client
+            .get_signatures_for_address_with_config(
+                user,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    limit: Some(page_limit),
+                    ..GetConfirmedSignaturesForAddress2Config::default()
+                },
+            )
+            .map_err(|_| ErrorCode::AccountFetchFailed)?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(
+            Signature::from_str(&last.signature).map_err(|_| ErrorCode::DeserializationError)?,
+        );
+        seen += page.len();
+        let full_page = page.len() == page_limit;
+        signatures.extend(
+            page.into_iter()
+                .filter(|status| status.err.is_none())
+                .filter_map(|status| Signature::from_str(&status.signature).ok()),
+        );
+        if !full_page {
+            break;
+        }
+    }
+
+    let commitment = rpc_client.commitment();
+    let mut history = TradeHistory::default();
+    // Signatures come newest first; walk them from the oldest.
+    signatures.reverse();
+    for chunk in signatures.chunks(TRANSACTION_FETCH_CONCURRENCY) {
+        let results = thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|signature| {
+                    scope.spawn(move || {
+                        fetch_transaction_events::<TradeEvent>(
+                            rpc_client, config, signature, commitment,
+                        )
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or(Err(ErrorCode::TransactionNotFound)))
+                .collect::<Vec<_>>()
+        });
+        for (signature, events) in chunk.iter().zip(results) {
+            match events {
+                Ok(events) => history
+                    .trades
+                    .extend(events.into_iter().filter(|trade| trade.user == *user)),
+                Err(_) => history.unreadable_signatures.push(*signature),
+            }
+        }
+    }
+    Ok(history)
+}
+
+/// PnL of `user` in every mint it traded within its last `history_limit`
+/// signatures, with open positions marked against their current curves.
+pub fn fetch_pnl(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    user: &Pubkey,
+    method: CostBasisMethod,
+    history_limit: usize,
+) -> Result<PnlReport, ErrorCode> {
+    let history = fetch_trade_history(rpc_client, config, user, history_limit)?;
+    let mut pnl: Vec<MintPnl> = compute_pnl(&history.trades, method).into_values().collect();
+    if pnl.is_empty() {
+        return Ok(PnlReport {
+            mints: pnl,
+            unreadable_signatures: history.unreadable_signatures,
+        });
+    }
+
+    let mints: Vec<Pubkey> = pnl.iter().map(|entry| entry.mint).collect();
+    let curves = fetch_bonding_curves(rpc_client, config, &mints);
+    let global = fetch_global(rpc_client, config)?;
+    for entry in pnl.iter_mut() {
+        if let Some(Ok(Some(curve))) = curves.get(&entry.mint) {
+            entry.mark_to_curve(curve, &global);
+        }
+    }
+    pnl.sort_by_key(|entry| entry.mint);
+    Ok(PnlReport {
+        mints: pnl,
+        unreadable_signatures: history.unreadable_signatures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::trade_event::tests::trade_event;
+    use serde_json::json;
+    use solana_client::{rpc_client::Mocks, rpc_request::RpcRequest};
+
+    fn trade(mint: Pubkey, is_buy: bool, token_amount: u64, sol_amount: u64) -> TradeEvent {
+        TradeEvent {
+            sol_amount,
+            token_amount,
+            fee: sol_amount / 100,
+            ..trade_event(mint, is_buy)
+        }
+    }
+
+    #[test]
+    fn test_fifo_and_average_cost_differ_on_partial_sells() {
+        let mint = Pubkey::new_unique();
+        let trades = [
+            trade(mint, true, 1_000_000, 1_000_000_000),
+            trade(mint, true, 1_000_000, 3_000_000_000),
+            trade(mint, false, 1_000_000, 4_000_000_000),
+        ];
+
+        let fifo = &compute_pnl(&trades, CostBasisMethod::Fifo)[&mint];
+        // Sold the first lot: 4.00 - 0.04 fees - 1.01 cost.
+        assert_eq!(fifo.realized_pnl, 2_950_000_000);
+        assert_eq!(fifo.position, 1_000_000);
+        assert_eq!(fifo.cost_basis, 3_030_000_000);
+        assert_eq!(fifo.fees_paid, 80_000_000);
+
+        let average = &compute_pnl(&trades, CostBasisMethod::AverageCost)[&mint];
+        // Sold half of a 4.04 SOL position.
+        assert_eq!(average.realized_pnl, 1_940_000_000);
+        assert_eq!(average.cost_basis, 2_020_000_000);
+        assert!((average.average_entry_price_in_sol - 2.02).abs() < 1e-9);
+
+        let oversold = compute_pnl(&[trade(mint, false, 500, 1_000)], CostBasisMethod::Fifo);
+        assert_eq!(oversold[&mint].untracked_tokens_sold, 500);
+    }
+
+    #[test]
+    fn test_zero_token_buy_is_realized_on_the_next_sell() {
+        let mint = Pubkey::new_unique();
+        let trades = [
+            trade(mint, true, 0, 1_000_000_000),
+            trade(mint, true, 1_000_000, 1_000_000_000),
+            trade(mint, false, 1_000_000, 2_000_000_000),
+        ];
+
+        for method in [CostBasisMethod::Fifo, CostBasisMethod::AverageCost] {
+            let pnl = &compute_pnl(&trades, method)[&mint];
+            // 2.00 - 0.02 fees - 2.02 cost of both buys.
+            assert_eq!(pnl.realized_pnl, -40_000_000);
+            assert_eq!(pnl.position, 0);
+            assert_eq!(pnl.cost_basis, 0);
+        }
+
+        let unmatched = compute_pnl(&trades[..1], CostBasisMethod::Fifo);
+        assert_eq!(unmatched[&mint].cost_basis, 1_010_000_000);
+    }
+
+    #[test]
+    fn test_unreadable_transactions_do_not_fail_the_history() {
+        let signature = Signature::new_unique();
+        let mut mocks = Mocks::default();
+        mocks.insert(
+            RpcRequest::GetSignaturesForAddress,
+            json!([{
+                "signature": signature.to_string(),
+                "slot": 1,
+                "err": null,
+                "memo": null,
+                "blockTime": null,
+                "confirmationStatus": "finalized",
+            }]),
+        );
+        mocks.insert(RpcRequest::GetTransaction, json!("not a transaction"));
+        let rpc_client = RpcClient::new_mock_with_mocks("succeeds", mocks);
+
+        let history = fetch_trade_history(
+            &rpc_client,
+            &PumpConfig::default(),
+            &Pubkey::new_unique(),
+            10,
+        )
+        .unwrap();
+        assert!(history.trades.is_empty());
+        assert_eq!(history.unreadable_signatures, vec![signature]);
+    }
+}
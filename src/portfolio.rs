@@ -0,0 +1,225 @@
+use std::{collections::HashMap, str::FromStr};
+
+use solana_account_decoder_client_types::UiAccountData;
+use solana_client::{rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    config::PumpConfig,
+    constants::{TOKEN_2022_PROGRAM, TOKEN_PROGRAM},
+    errors::ErrorCode,
+    fetch::{fetch_bonding_curves, fetch_global, fetch_multiple_accounts},
+    pump_amm::pda::get_canonical_pool_pda,
+    states::{BondingCurve, Global},
+};
+
+/// A token account of the wallet, as listed by `getTokenAccountsByOwner`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenHolding {
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub amount: u64,
+}
+
+/// Where a position can currently be traded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Market {
+    BondingCurve,
+    /// The curve is complete but its AMM pool does not exist yet, so the
+    /// token cannot be traded until it migrates.
+    PendingMigration,
+    PumpAmm,
+}
+
+/// A wallet's balance of one pump.fun token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub token_program: Pubkey,
+    pub balance: u64,
+    /// Lamports selling the whole balance into the curve would return after
+    /// fees. `None` once the curve is complete.
+    pub value: Option<u64>,
+    /// Share of the curve's tokens sold, between 0 and 1.
+    pub progress: f64,
+    pub market: Market,
+}
+
+impl Position {
+    /// `pool_exists` tells whether the mint's canonical AMM pool has been
+    /// created; it only matters once the curve is complete.
+    pub fn new(
+        holding: &TokenHolding,
+        curve: &BondingCurve,
+        global: &Global,
+        pool_exists: bool,
+    ) -> Self {
+        let value = if curve.complete {
+            None
+        } else {
+            curve
+                .sell_quote(
+                    holding.amount,
+                    global.trade_fee_basis_points(&curve.creator),
+                )
+                .ok()
+        };
+        Self {
+            mint: holding.mint,
+            token_account: holding.token_account,
+            token_program: holding.token_program,
+            balance: holding.amount,
+            value,
+            progress: curve.progress(),
+            market: match (curve.complete, pool_exists) {
+                (false, _) => Market::BondingCurve,
+                (true, false) => Market::PendingMigration,
+                (true, true) => Market::PumpAmm,
+            },
+        }
+    }
+}
+
+/// A wallet's positions, plus the mints that could not be valued.
+#[derive(Debug, Clone, Default)]
+pub struct Portfolio {
+    pub positions: Vec<Position>,
+    /// Mints whose curve or pool could not be fetched or decoded; their
+    /// positions are left out rather than failing the whole portfolio.
+    pub skipped: Vec<(Pubkey, ErrorCode)>,
+}
+
+/// Every non-empty SPL Token and Token-2022 account `user` owns.
+pub fn fetch_token_holdings(
+    rpc_client: &RpcClient,
+    user: &Pubkey,
+) -> Result<Vec<TokenHolding>, ErrorCode> {
+    let mut holdings = vec![];
+    for token_program in [TOKEN_PROGRAM, TOKEN_2022_PROGRAM] {
+        let accounts = rpc_client
+            .get_token_accounts_by_owner(user, TokenAccountsFilter::ProgramId(token_program))
+            .map_err(|_| ErrorCode::AccountFetchFailed)?;
+        holdings.extend(accounts.into_iter().filter_map(|keyed| {
+            let UiAccountData::Json(parsed) = keyed.account.data else {
+                return None;
+            };
+            let info = &parsed.parsed["info"];
+            Some(TokenHolding {
+                token_account: Pubkey::from_str(&keyed.pubkey).ok()?,
+                mint: Pubkey::from_str(info["mint"].as_str()?).ok()?,
+                token_program,
+                amount: info["tokenAmount"]["amount"].as_str()?.parse().ok()?,
+            })
+        }));
+    }
+    holdings.retain(|holding| holding.amount > 0);
+    Ok(holdings)
+}
+
+/// Lists `user`'s pump.fun positions: every token account whose mint has a
+/// bonding curve, valued against that curve. Curves, and the AMM pools of
+/// complete curves, are loaded with batched `getMultipleAccounts` calls, so
+/// the cost does not grow per token. A mint whose accounts cannot be read is
+/// reported in [`Portfolio::skipped`].
+pub fn portfolio(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    user: &Pubkey,
+) -> Result<Portfolio, ErrorCode> {
+    let holdings = fetch_token_holdings(rpc_client, user)?;
+    if holdings.is_empty() {
+        return Ok(Portfolio::default());
+    }
+
+    let mut mints: Vec<Pubkey> = holdings.iter().map(|holding| holding.mint).collect();
+    mints.sort();
+    mints.dedup();
+    let mut curves = HashMap::new();
+    let mut skipped = vec![];
+    for (mint, curve) in fetch_bonding_curves(rpc_client, config, &mints) {
+        match curve {
+            Ok(Some(curve)) => {
+                curves.insert(mint, curve);
+            }
+            Ok(None) => {}
+            Err(error) => skipped.push((mint, error)),
+        }
+    }
+    if curves.is_empty() {
+        return Ok(Portfolio {
+            positions: vec![],
+            skipped,
+        });
+    }
+
+    let complete: Vec<Pubkey> = curves
+        .iter()
+        .filter(|(_, curve)| curve.complete)
+        .map(|(mint, _)| *mint)
+        .collect();
+    let pools: Vec<Pubkey> = complete
+        .iter()
+        .map(|mint| get_canonical_pool_pda(config, mint))
+        .collect();
+    let mut pool_exists = HashMap::new();
+    for (mint, pool) in complete
+        .into_iter()
+        .zip(fetch_multiple_accounts(rpc_client, &pools))
+    {
+        match pool {
+            Ok(pool) => {
+                pool_exists.insert(mint, pool.is_some());
+            }
+            Err(error) => {
+                curves.remove(&mint);
+                skipped.push((mint, error));
+            }
+        }
+    }
+
+    let global = fetch_global(rpc_client, config)?;
+    let positions = holdings
+        .iter()
+        .filter_map(|holding| {
+            let curve = curves.get(&holding.mint)?;
+            let pool_exists = pool_exists.get(&holding.mint).copied().unwrap_or(false);
+            Some(Position::new(holding, curve, &global, pool_exists))
+        })
+        .collect();
+    Ok(Portfolio { positions, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::bonding_curve::tests::global;
+
+    #[test]
+    fn test_position_values_active_curves_only() {
+        let global = global();
+        let mut curve = BondingCurve::initial(&global, Pubkey::new_unique());
+        curve.apply_buy(100_000_000_000_000).unwrap();
+        let holding = TokenHolding {
+            token_account: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            token_program: TOKEN_PROGRAM,
+            amount: 10_000_000_000_000,
+        };
+
+        let position = Position::new(&holding, &curve, &global, false);
+        let gross = curve.sell_quote(holding.amount, 0).unwrap();
+        let value = position.value.unwrap();
+        assert!(value < gross && value > gross * 98 / 100);
+        assert!(position.progress > 0.1 && position.progress < 0.15);
+        assert_eq!(position.market, Market::BondingCurve);
+
+        curve.complete = true;
+        let position = Position::new(&holding, &curve, &global, false);
+        assert_eq!(position.value, None);
+        assert_eq!(position.market, Market::PendingMigration);
+        let position = Position::new(&holding, &curve, &global, true);
+        assert_eq!(position.market, Market::PumpAmm);
+    }
+}
@@ -0,0 +1,283 @@
+use anchor_lang::prelude::*;
+use core::result::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token::instruction::{close_account, sync_native};
+
+use crate::{
+    config::PumpConfig,
+    constants::{ASSOCIATED_TOKEN_PROGRAM, SYSTEM_PROGRAM, TOKEN_PROGRAM, WSOL_MINT},
+    errors::ErrorCode,
+    fetch::fetch_token_program,
+    instructions::buy::{max_sol_cost_with_slippage, Buy, BuyAccounts},
+};
+
+use super::{
+    pda::{
+        get_coin_creator_vault_ata, get_coin_creator_vault_authority_pda, get_event_authority_pda,
+    },
+    PoolSnapshot,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AmmBuyArgs {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
+    pub base_amount_out: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
+    pub max_quote_amount_in: u64,
+}
+
+/// Builds the pool's `buy` instruction from a loaded snapshot, without
+/// touching the chain. `base_token_program` is the program that owns the
+/// base mint, SPL Token or Token-2022.
+pub fn build_amm_buy_ix(
+    config: &PumpConfig,
+    snapshot: &PoolSnapshot,
+    user: &Pubkey,
+    args: &AmmBuyArgs,
+    protocol_fee_recipient: &Pubkey,
+    base_token_program: &Pubkey,
+) -> Instruction {
+    let discriminator = [102, 6, 61, 18, 1, 218, 235, 234];
+    let mut data = discriminator.to_vec();
+
+    let user_base_token_account = get_associated_token_address_with_program_id(
+        user,
+        &snapshot.pool.base_mint,
+        base_token_program,
+    );
+    let user_quote_token_account = get_associated_token_address(user, &WSOL_MINT);
+    let accounts_metas = swap_account_metas(
+        snapshot,
+        user,
+        &user_base_token_account,
+        &user_quote_token_account,
+        protocol_fee_recipient,
+        base_token_program,
+        config,
+    );
+
+    data.extend(args.try_to_vec().unwrap());
+
+    Instruction {
+        program_id: config.amm_program_id,
+        accounts: accounts_metas,
+        data,
+    }
+}
+
+/// Buys `args.amount` tokens of a migrated mint from its pump AMM pool,
+/// paying at most `args.max_sol_cost` (plus slippage) in SOL. The SOL is
+/// wrapped into the user's WSOL account before the swap and whatever is left
+/// is unwrapped afterwards.
+pub fn buy_ix(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    accounts: BuyAccounts,
+    args: Buy,
+) -> Result<Vec<Instruction>, ErrorCode> {
+    let snapshot = PoolSnapshot::fetch(rpc_client, config, &accounts.mint)?;
+    let base_token_program = fetch_token_program(rpc_client, &accounts.mint)?;
+    if args.amount == 0 {
+        return Err(ErrorCode::BuyZeroAmount);
+    }
+    if args.amount >= snapshot.base_reserve {
+        return Err(ErrorCode::NotEnoughTokensToBuy);
+    }
+
+    let max_quote_amount_in = max_sol_cost_with_slippage(args.max_sol_cost, args.slippage)?;
+
+    let protocol_fee_recipient = snapshot
+        .global_config
+        .protocol_fee_recipient()
+        .ok_or(ErrorCode::GlobalNotFound)?;
+    let user_quote_token_account = get_associated_token_address(&accounts.user, &WSOL_MINT);
+
+    let buy_args = AmmBuyArgs {
+        base_amount_out: args.amount,
+        max_quote_amount_in,
+    };
+
+    Ok(vec![
+        create_associated_token_account_idempotent(
+            &accounts.user,
+            &accounts.user,
+            &accounts.mint,
+            &base_token_program,
+        ),
+        create_associated_token_account_idempotent(
+            &accounts.user,
+            &accounts.user,
+            &WSOL_MINT,
+            &TOKEN_PROGRAM,
+        ),
+        transfer(
+            &accounts.user,
+            &user_quote_token_account,
+            max_quote_amount_in,
+        ),
+        sync_native(&TOKEN_PROGRAM, &user_quote_token_account)
+            .map_err(|_| ErrorCode::TokenInstructionFailed)?,
+        build_amm_buy_ix(
+            config,
+            &snapshot,
+            &accounts.user,
+            &buy_args,
+            &protocol_fee_recipient,
+            &base_token_program,
+        ),
+        close_account(
+            &TOKEN_PROGRAM,
+            &user_quote_token_account,
+            &accounts.user,
+            &accounts.user,
+            &[],
+        )
+        .map_err(|_| ErrorCode::TokenInstructionFailed)?,
+    ])
+}
+
+/// Account list shared by the pool's `buy` and `sell` instructions.
+pub(crate) fn swap_account_metas(
+    snapshot: &PoolSnapshot,
+    user: &Pubkey,
+    user_base_token_account: &Pubkey,
+    user_quote_token_account: &Pubkey,
+    protocol_fee_recipient: &Pubkey,
+    base_token_program: &Pubkey,
+    config: &PumpConfig,
+) -> Vec<AccountMeta> {
+    let pool = &snapshot.pool;
+    let protocol_fee_recipient_token_account =
+        get_associated_token_address(protocol_fee_recipient, &pool.quote_mint);
+
+    vec![
+        AccountMeta::new(snapshot.address, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(super::pda::get_global_config_pda(config), false),
+        AccountMeta::new_readonly(pool.base_mint, false),
+        AccountMeta::new_readonly(pool.quote_mint, false),
+        AccountMeta::new(*user_base_token_account, false),
+        AccountMeta::new(*user_quote_token_account, false),
+        AccountMeta::new(pool.pool_base_token_account, false),
+        AccountMeta::new(pool.pool_quote_token_account, false),
+        AccountMeta::new_readonly(*protocol_fee_recipient, false),
+        AccountMeta::new(protocol_fee_recipient_token_account, false),
+        AccountMeta::new_readonly(*base_token_program, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM, false),
+        AccountMeta::new_readonly(get_event_authority_pda(config), false),
+        AccountMeta::new_readonly(config.amm_program_id, false),
+        AccountMeta::new(
+            get_coin_creator_vault_ata(config, &pool.coin_creator),
+            false,
+        ),
+        AccountMeta::new_readonly(
+            get_coin_creator_vault_authority_pda(config, &pool.coin_creator),
+            false,
+        ),
+    ]
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        constants::TOKEN_2022_PROGRAM,
+        pump_amm::{
+            pda::{get_canonical_pool_pda, get_global_config_pda},
+            GlobalConfig, Pool,
+        },
+    };
+
+    pub(crate) fn snapshot(config: &PumpConfig) -> PoolSnapshot {
+        let base_mint = Pubkey::new_unique();
+        let mut protocol_fee_recipients = [Pubkey::default(); 8];
+        protocol_fee_recipients[2] = Pubkey::new_unique();
+        PoolSnapshot {
+            address: get_canonical_pool_pda(config, &base_mint),
+            pool: Pool {
+                pool_bump: 255,
+                index: 0,
+                creator: Pubkey::new_unique(),
+                base_mint,
+                quote_mint: WSOL_MINT,
+                lp_mint: Pubkey::new_unique(),
+                pool_base_token_account: Pubkey::new_unique(),
+                pool_quote_token_account: Pubkey::new_unique(),
+                lp_supply: 0,
+                coin_creator: Pubkey::new_unique(),
+            },
+            global_config: GlobalConfig {
+                admin: Pubkey::new_unique(),
+                lp_fee_basis_points: 20,
+                protocol_fee_basis_points: 5,
+                disable_flags: 0,
+                protocol_fee_recipients,
+                coin_creator_fee_basis_points: 5,
+                admin_set_coin_creator_authority: Pubkey::new_unique(),
+            },
+            base_reserve: 200_000_000_000_000,
+            quote_reserve: 85_000_000_000,
+        }
+    }
+
+    #[test]
+    fn test_build_amm_buy_ix_account_order() {
+        let config = PumpConfig::default();
+        let snapshot = snapshot(&config);
+        let user = Pubkey::new_unique();
+        let recipient = snapshot.global_config.protocol_fee_recipient().unwrap();
+        let args = AmmBuyArgs {
+            base_amount_out: 1_000,
+            max_quote_amount_in: 2_000,
+        };
+
+        let ix = build_amm_buy_ix(
+            &config,
+            &snapshot,
+            &user,
+            &args,
+            &recipient,
+            &TOKEN_2022_PROGRAM,
+        );
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(ix.program_id, config.amm_program_id);
+        assert_eq!(keys.len(), 19);
+        assert_eq!(keys[0], snapshot.address);
+        assert_eq!((keys[1], ix.accounts[1].is_signer), (user, true));
+        assert_eq!(keys[2], get_global_config_pda(&config));
+        assert_eq!(keys[3..5], [snapshot.pool.base_mint, WSOL_MINT]);
+        assert_eq!(
+            keys[5],
+            get_associated_token_address_with_program_id(
+                &user,
+                &snapshot.pool.base_mint,
+                &TOKEN_2022_PROGRAM
+            )
+        );
+        assert_eq!(keys[6], get_associated_token_address(&user, &WSOL_MINT));
+        assert_eq!(
+            keys[7..9],
+            [
+                snapshot.pool.pool_base_token_account,
+                snapshot.pool.pool_quote_token_account
+            ]
+        );
+        assert_eq!(keys[9], recipient);
+        assert_eq!(keys[11..13], [TOKEN_2022_PROGRAM, TOKEN_PROGRAM]);
+        assert_eq!(keys[16], config.amm_program_id);
+        assert_eq!(ix.data[..8], [102, 6, 61, 18, 1, 218, 235, 234]);
+        assert_eq!(ix.data[8..16], 1_000u64.to_le_bytes());
+    }
+}
@@ -0,0 +1,21 @@
+//! Trading against the pump AMM pool a token migrates to once its bonding
+//! curve completes.
+
+use anchor_lang::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+pub mod pda;
+
+pub mod states;
+pub use states::*;
+
+pub mod quote;
+pub use quote::*;
+
+pub mod buy;
+pub use buy::*;
+
+pub mod sell;
+pub use sell::*;
+
+pub const PUMP_AMM_PROGRAM_ID: Pubkey = pubkey!("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA");
@@ -0,0 +1,79 @@
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::{config::PumpConfig, constants::WSOL_MINT};
+
+const POOL_SEED: &[u8] = b"pool";
+
+const GLOBAL_CONFIG_SEED: &[u8] = b"global_config";
+
+const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+const CREATOR_VAULT_SEED: &[u8] = b"creator_vault";
+
+/// Seed of the pump.fun PDA that creates the pool during `migrate`.
+const POOL_AUTHORITY_SEED: &[u8] = b"pool-authority";
+
+pub fn get_pool_pda(
+    config: &PumpConfig,
+    index: u16,
+    creator: &Pubkey,
+    base_mint: &Pubkey,
+    quote_mint: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            POOL_SEED,
+            index.to_le_bytes().as_slice(),
+            creator.to_bytes().as_slice(),
+            base_mint.to_bytes().as_slice(),
+            quote_mint.to_bytes().as_slice(),
+        ],
+        &config.amm_program_id,
+    )
+    .0
+}
+
+/// Authority on the pump.fun side that owns every pool created by migration.
+pub fn get_pool_authority_pda(config: &PumpConfig, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[POOL_AUTHORITY_SEED, mint.to_bytes().as_slice()],
+        &config.program_id,
+    )
+    .0
+}
+
+/// The pool a graduated pump.fun token is migrated into: index 0, created by
+/// the pool authority and quoted in WSOL.
+pub fn get_canonical_pool_pda(config: &PumpConfig, mint: &Pubkey) -> Pubkey {
+    get_pool_pda(
+        config,
+        0,
+        &get_pool_authority_pda(config, mint),
+        mint,
+        &WSOL_MINT,
+    )
+}
+
+pub fn get_global_config_pda(config: &PumpConfig) -> Pubkey {
+    Pubkey::find_program_address(&[GLOBAL_CONFIG_SEED], &config.amm_program_id).0
+}
+
+pub fn get_event_authority_pda(config: &PumpConfig) -> Pubkey {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &config.amm_program_id).0
+}
+
+pub fn get_coin_creator_vault_authority_pda(config: &PumpConfig, coin_creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[CREATOR_VAULT_SEED, coin_creator.to_bytes().as_slice()],
+        &config.amm_program_id,
+    )
+    .0
+}
+
+pub fn get_coin_creator_vault_ata(config: &PumpConfig, coin_creator: &Pubkey) -> Pubkey {
+    get_associated_token_address(
+        &get_coin_creator_vault_authority_pda(config, coin_creator),
+        &WSOL_MINT,
+    )
+}
@@ -0,0 +1,260 @@
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use spl_token::{solana_program::program_pack::Pack, state::Account as TokenAccount};
+
+use crate::{config::PumpConfig, errors::ErrorCode, fetch::fetch_multiple_accounts};
+
+use super::{
+    pda::{get_canonical_pool_pda, get_global_config_pda},
+    GlobalConfig, Pool,
+};
+
+const BASIS_POINTS: u128 = 10_000;
+
+/// Fees charged by the pool on the quote side of every swap.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AmmFees {
+    pub lp_fee_basis_points: u64,
+    pub protocol_fee_basis_points: u64,
+    pub coin_creator_fee_basis_points: u64,
+}
+
+impl AmmFees {
+    /// The coin creator fee only applies to pools that have a coin creator.
+    pub fn new(global_config: &GlobalConfig, pool: &Pool) -> Self {
+        let coin_creator_fee_basis_points = if pool.coin_creator == Pubkey::default() {
+            0
+        } else {
+            global_config.coin_creator_fee_basis_points
+        };
+        Self {
+            lp_fee_basis_points: global_config.lp_fee_basis_points,
+            protocol_fee_basis_points: global_config.protocol_fee_basis_points,
+            coin_creator_fee_basis_points,
+        }
+    }
+
+    fn total_basis_points(&self) -> u128 {
+        self.lp_fee_basis_points as u128
+            + self.protocol_fee_basis_points as u128
+            + self.coin_creator_fee_basis_points as u128
+    }
+
+    /// Each fee is rounded up separately, matching the program.
+    fn on(&self, amount: u128) -> Result<u128, ErrorCode> {
+        [
+            self.lp_fee_basis_points,
+            self.protocol_fee_basis_points,
+            self.coin_creator_fee_basis_points,
+        ]
+        .iter()
+        .try_fold(0u128, |total, bps| {
+            let fee = ceil_div(
+                amount
+                    .checked_mul(*bps as u128)
+                    .ok_or(ErrorCode::Overflow)?,
+                BASIS_POINTS,
+            )?;
+            total.checked_add(fee).ok_or(ErrorCode::Overflow)
+        })
+    }
+}
+
+fn ceil_div(numerator: u128, denominator: u128) -> Result<u128, ErrorCode> {
+    if denominator == 0 {
+        return Err(ErrorCode::Overflow);
+    }
+    Ok(numerator.div_ceil(denominator))
+}
+
+fn to_u64(value: u128) -> Result<u64, ErrorCode> {
+    value.try_into().map_err(|_| ErrorCode::Overflow)
+}
+
+/// Quote tokens, fees included, needed to receive exactly `base_amount_out`.
+pub fn buy_quote_input(
+    base_amount_out: u64,
+    base_reserve: u64,
+    quote_reserve: u64,
+    fees: &AmmFees,
+) -> Result<u64, ErrorCode> {
+    if base_amount_out == 0 {
+        return Err(ErrorCode::BuyZeroAmount);
+    }
+    if base_amount_out >= base_reserve {
+        return Err(ErrorCode::NotEnoughTokensToBuy);
+    }
+    let quote_in = ceil_div(
+        (quote_reserve as u128)
+            .checked_mul(base_amount_out as u128)
+            .ok_or(ErrorCode::Overflow)?,
+        (base_reserve - base_amount_out) as u128,
+    )?;
+    to_u64(quote_in + fees.on(quote_in)?)
+}
+
+/// Base tokens received for spending `quote_amount_in`, fees included.
+pub fn buy_base_output(
+    quote_amount_in: u64,
+    base_reserve: u64,
+    quote_reserve: u64,
+    fees: &AmmFees,
+) -> Result<u64, ErrorCode> {
+    let effective_quote =
+        (quote_amount_in as u128) * BASIS_POINTS / (BASIS_POINTS + fees.total_basis_points());
+    let denominator = (quote_reserve as u128)
+        .checked_add(effective_quote)
+        .ok_or(ErrorCode::Overflow)?;
+    if denominator == 0 {
+        return Err(ErrorCode::Overflow);
+    }
+    to_u64((base_reserve as u128) * effective_quote / denominator)
+}
+
+/// Quote tokens received, after fees, for selling `base_amount_in`.
+pub fn sell_quote_output(
+    base_amount_in: u64,
+    base_reserve: u64,
+    quote_reserve: u64,
+    fees: &AmmFees,
+) -> Result<u64, ErrorCode> {
+    if base_amount_in == 0 {
+        return Err(ErrorCode::SellZeroAmount);
+    }
+    let denominator = (base_reserve as u128) + (base_amount_in as u128);
+    let quote_out = (quote_reserve as u128) * (base_amount_in as u128) / denominator;
+    let fee = fees.on(quote_out)?;
+    to_u64(quote_out.saturating_sub(fee))
+}
+
+/// A pool together with its config and current reserves.
+#[derive(Debug, Clone)]
+pub struct PoolSnapshot {
+    pub address: Pubkey,
+    pub pool: Pool,
+    pub global_config: GlobalConfig,
+    pub base_reserve: u64,
+    pub quote_reserve: u64,
+}
+
+impl PoolSnapshot {
+    /// Loads the canonical pool of a migrated mint.
+    pub fn fetch(
+        rpc_client: &RpcClient,
+        config: &PumpConfig,
+        mint: &Pubkey,
+    ) -> Result<Self, ErrorCode> {
+        let address = get_canonical_pool_pda(config, mint);
+        let mut accounts =
+            fetch_multiple_accounts(rpc_client, &[address, get_global_config_pda(config)])
+                .into_iter();
+
+        let pool = accounts
+            .next()
+            .and_then(|account| account.ok().flatten())
+            .ok_or(ErrorCode::PoolNotFound)?;
+        let pool = Pool::from_account_data(&pool.data)?;
+        let global_config = accounts
+            .next()
+            .and_then(|account| account.ok().flatten())
+            .ok_or(ErrorCode::GlobalNotFound)?;
+        let global_config = GlobalConfig::from_account_data(&global_config.data)?;
+
+        let mut reserves = fetch_multiple_accounts(
+            rpc_client,
+            &[pool.pool_base_token_account, pool.pool_quote_token_account],
+        )
+        .into_iter()
+        .map(|account| {
+            let account = account?.ok_or(ErrorCode::PoolNotFound)?;
+            TokenAccount::unpack_from_slice(&account.data)
+                .map(|token_account| token_account.amount)
+                .map_err(|_| ErrorCode::DeserializationError)
+        });
+        let base_reserve = reserves.next().ok_or(ErrorCode::PoolNotFound)??;
+        let quote_reserve = reserves.next().ok_or(ErrorCode::PoolNotFound)??;
+
+        Ok(Self {
+            address,
+            pool,
+            global_config,
+            base_reserve,
+            quote_reserve,
+        })
+    }
+
+    pub fn fees(&self) -> AmmFees {
+        AmmFees::new(&self.global_config, &self.pool)
+    }
+
+    pub fn buy_quote_input(&self, base_amount_out: u64) -> Result<u64, ErrorCode> {
+        buy_quote_input(
+            base_amount_out,
+            self.base_reserve,
+            self.quote_reserve,
+            &self.fees(),
+        )
+    }
+
+    pub fn buy_base_output(&self, quote_amount_in: u64) -> Result<u64, ErrorCode> {
+        buy_base_output(
+            quote_amount_in,
+            self.base_reserve,
+            self.quote_reserve,
+            &self.fees(),
+        )
+    }
+
+    pub fn sell_quote_output(&self, base_amount_in: u64) -> Result<u64, ErrorCode> {
+        sell_quote_output(
+            base_amount_in,
+            self.base_reserve,
+            self.quote_reserve,
+            &self.fees(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEES: AmmFees = AmmFees {
+        lp_fee_basis_points: 20,
+        protocol_fee_basis_points: 5,
+        coin_creator_fee_basis_points: 5,
+    };
+
+    #[test]
+    fn test_buy_quote_round_trips_through_base_output() {
+        let (base, quote) = (200_000_000_000_000, 85_000_000_000);
+        let quote_in = buy_quote_input(1_000_000_000_000, base, quote, &FEES).unwrap();
+        let base_out = buy_base_output(quote_in, base, quote, &FEES).unwrap();
+
+        // Each direction rounds in the pool's favour, so allow a tiny drift.
+        assert!(base_out.abs_diff(1_000_000_000_000) < 10_000_000);
+    }
+
+    #[test]
+    fn test_sell_quote_output_deducts_fees() {
+        let (base, quote) = (200_000_000_000_000, 85_000_000_000);
+        let no_fees =
+            sell_quote_output(1_000_000_000_000, base, quote, &AmmFees::default()).unwrap();
+        let with_fees = sell_quote_output(1_000_000_000_000, base, quote, &FEES).unwrap();
+
+        assert_eq!(no_fees, 85_000_000_000 / 201);
+        assert!(with_fees < no_fees);
+        assert!(matches!(
+            sell_quote_output(0, base, quote, &FEES),
+            Err(ErrorCode::SellZeroAmount)
+        ));
+    }
+
+    #[test]
+    fn test_buy_rejects_draining_the_pool() {
+        assert!(matches!(
+            buy_quote_input(100, 100, 1_000, &FEES),
+            Err(ErrorCode::NotEnoughTokensToBuy)
+        ));
+    }
+}
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use core::result::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token::instruction::close_account;
+
+use crate::{
+    config::PumpConfig,
+    constants::{TOKEN_PROGRAM, WSOL_MINT},
+    errors::ErrorCode,
+    fetch::{fetch_token_balance, fetch_token_program},
+    instructions::sell::{min_sol_output_with_slippage, Sell, SellAccounts},
+};
+
+use super::{buy::swap_account_metas, PoolSnapshot};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AmmSellArgs {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
+    pub base_amount_in: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
+    pub min_quote_amount_out: u64,
+}
+
+/// Builds the pool's `sell` instruction from a loaded snapshot, without
+/// touching the chain. `base_token_program` is the program that owns the
+/// base mint, SPL Token or Token-2022.
+pub fn build_amm_sell_ix(
+    config: &PumpConfig,
+    snapshot: &PoolSnapshot,
+    user: &Pubkey,
+    args: &AmmSellArgs,
+    protocol_fee_recipient: &Pubkey,
+    base_token_program: &Pubkey,
+) -> Instruction {
+    let discriminator = [51, 230, 133, 164, 1, 127, 131, 173];
+    let mut data = discriminator.to_vec();
+
+    let user_base_token_account = get_associated_token_address_with_program_id(
+        user,
+        &snapshot.pool.base_mint,
+        base_token_program,
+    );
+    let user_quote_token_account = get_associated_token_address(user, &WSOL_MINT);
+    let accounts_metas = swap_account_metas(
+        snapshot,
+        user,
+        &user_base_token_account,
+        &user_quote_token_account,
+        protocol_fee_recipient,
+        base_token_program,
+        config,
+    );
+
+    data.extend(args.try_to_vec().unwrap());
+
+    Instruction {
+        program_id: config.amm_program_id,
+        accounts: accounts_metas,
+        data,
+    }
+}
+
+/// Sells `args.amount` tokens of a migrated mint into its pump AMM pool,
+/// receiving at least `args.min_sol_output` (minus slippage). The proceeds
+/// land in the user's WSOL account, which is closed to unwrap them.
+pub fn sell_ix(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    accounts: SellAccounts,
+    args: Sell,
+) -> Result<Vec<Instruction>, ErrorCode> {
+    let snapshot = PoolSnapshot::fetch(rpc_client, config, &accounts.mint)?;
+    let base_token_program = fetch_token_program(rpc_client, &accounts.mint)?;
+    let user_base_token_account = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.mint,
+        &base_token_program,
+    );
+
+    if args.amount == 0 {
+        return Err(ErrorCode::SellZeroAmount);
+    }
+    let user_token_balance = fetch_token_balance(rpc_client, &user_base_token_account)?;
+    if args.amount > user_token_balance {
+        return Err(ErrorCode::NotEnoughTokensToSell);
+    }
+
+    let min_quote_amount_out = min_sol_output_with_slippage(args.min_sol_output, args.slippage)?;
+
+    let protocol_fee_recipient = snapshot
+        .global_config
+        .protocol_fee_recipient()
+        .ok_or(ErrorCode::GlobalNotFound)?;
+    let user_quote_token_account = get_associated_token_address(&accounts.user, &WSOL_MINT);
+
+    let sell_args = AmmSellArgs {
+        base_amount_in: args.amount,
+        min_quote_amount_out,
+    };
+
+    Ok(vec![
+        create_associated_token_account_idempotent(
+            &accounts.user,
+            &accounts.user,
+            &WSOL_MINT,
+            &TOKEN_PROGRAM,
+        ),
+        build_amm_sell_ix(
+            config,
+            &snapshot,
+            &accounts.user,
+            &sell_args,
+            &protocol_fee_recipient,
+            &base_token_program,
+        ),
+        close_account(
+            &TOKEN_PROGRAM,
+            &user_quote_token_account,
+            &accounts.user,
+            &accounts.user,
+            &[],
+        )
+        .map_err(|_| ErrorCode::TokenInstructionFailed)?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pump_amm::buy::tests::snapshot;
+
+    #[test]
+    fn test_build_amm_sell_ix_account_order() {
+        let config = PumpConfig::default();
+        let snapshot = snapshot(&config);
+        let user = Pubkey::new_unique();
+        let recipient = snapshot.global_config.protocol_fee_recipient().unwrap();
+        let args = AmmSellArgs {
+            base_amount_in: 1_000,
+            min_quote_amount_out: 500,
+        };
+
+        let ix = build_amm_sell_ix(&config, &snapshot, &user, &args, &recipient, &TOKEN_PROGRAM);
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(keys.len(), 19);
+        assert_eq!(keys[0], snapshot.address);
+        assert_eq!((keys[1], ix.accounts[1].is_signer), (user, true));
+        assert_eq!(
+            keys[5],
+            get_associated_token_address(&user, &snapshot.pool.base_mint)
+        );
+        assert_eq!(keys[6], get_associated_token_address(&user, &WSOL_MINT));
+        assert_eq!(keys[9], recipient);
+        assert_eq!(
+            keys[10],
+            get_associated_token_address(&recipient, &WSOL_MINT)
+        );
+        assert_eq!(ix.data[..8], [51, 230, 133, 164, 1, 127, 131, 173]);
+        assert_eq!(ix.data[16..24], 500u64.to_le_bytes());
+    }
+}
@@ -0,0 +1,59 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::errors::ErrorCode;
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct Pool {
+    pub pool_bump: u8,
+    pub index: u16,
+    pub creator: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub pool_base_token_account: Pubkey,
+    pub pool_quote_token_account: Pubkey,
+    pub lp_supply: u64,
+    pub coin_creator: Pubkey,
+}
+
+impl Pool {
+    pub const DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ErrorCode> {
+        let payload = data
+            .strip_prefix(Self::DISCRIMINATOR.as_slice())
+            .ok_or(ErrorCode::DeserializationError)?;
+        Self::deserialize(&mut &payload[..]).map_err(|_| ErrorCode::DeserializationError)
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct GlobalConfig {
+    pub admin: Pubkey,
+    pub lp_fee_basis_points: u64,
+    pub protocol_fee_basis_points: u64,
+    pub disable_flags: u8,
+    pub protocol_fee_recipients: [Pubkey; 8],
+    pub coin_creator_fee_basis_points: u64,
+    pub admin_set_coin_creator_authority: Pubkey,
+}
+
+impl GlobalConfig {
+    pub const DISCRIMINATOR: [u8; 8] = [149, 8, 156, 202, 160, 252, 176, 217];
+
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ErrorCode> {
+        let payload = data
+            .strip_prefix(Self::DISCRIMINATOR.as_slice())
+            .ok_or(ErrorCode::DeserializationError)?;
+        Self::deserialize(&mut &payload[..]).map_err(|_| ErrorCode::DeserializationError)
+    }
+
+    /// First configured protocol fee recipient; unused slots are zeroed.
+    pub fn protocol_fee_recipient(&self) -> Option<Pubkey> {
+        self.protocol_fee_recipients
+            .iter()
+            .find(|recipient| **recipient != Pubkey::default())
+            .copied()
+    }
+}
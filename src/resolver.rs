@@ -0,0 +1,265 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use solana_account_decoder_client_types::UiAccountData;
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_request::TokenAccountsFilter,
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::{
+    config::PumpConfig,
+    constants::{TOKEN_2022_PROGRAM, TOKEN_PROGRAM},
+    errors::ErrorCode,
+    events::{fetch_transaction_events, CreateEvent},
+    pda::get_bonding_curve_pda,
+};
+
+/// Page size used when walking a curve's signature history back to its creation.
+const SIGNATURE_PAGE_LIMIT: usize = 1000;
+/// Pages walked before giving up on finding a curve's creation.
+const MAX_SIGNATURE_PAGES: usize = 20;
+/// Mints resolved by [`MintIndex::resolve`] between two saves.
+const SAVE_BATCH: usize = 64;
+
+/// Two-way mint <-> bonding curve index. Every pair is checked against
+/// [`get_bonding_curve_pda`] before it is stored, and the index can be
+/// persisted to a plain text file with one `curve mint` pair per line.
+/// Pairs learned by [`MintIndex::resolve`] are saved in batches and on drop.
+#[derive(Debug, Default)]
+pub struct MintIndex {
+    config: PumpConfig,
+    path: Option<PathBuf>,
+    curve_to_mint: HashMap<Pubkey, Pubkey>,
+    mint_to_curve: HashMap<Pubkey, Pubkey>,
+    unsaved: usize,
+}
+
+impl MintIndex {
+    /// Opens the index stored at `path`, starting empty if the file does not exist yet.
+    pub fn open(path: impl AsRef<Path>, config: &PumpConfig) -> Result<Self, ErrorCode> {
+        let path = path.as_ref().to_path_buf();
+        let mut index = Self {
+            config: *config,
+            path: Some(path.clone()),
+            curve_to_mint: HashMap::new(),
+            mint_to_curve: HashMap::new(),
+            unsaved: 0,
+        };
+        if !path.exists() {
+            return Ok(index);
+        }
+
+        let contents = fs::read_to_string(&path).map_err(|_| ErrorCode::MintIndexIo)?;
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            let mut parts = line.split_whitespace();
+            let (Some(curve), Some(mint)) = (parts.next(), parts.next()) else {
+                return Err(ErrorCode::DeserializationError);
+            };
+            let curve = Pubkey::from_str(curve).map_err(|_| ErrorCode::DeserializationError)?;
+            let mint = Pubkey::from_str(mint).map_err(|_| ErrorCode::DeserializationError)?;
+            index.insert_pair(mint, curve)?;
+        }
+        Ok(index)
+    }
+
+    /// Records `mint` and returns its derived bonding curve.
+    pub fn insert(&mut self, mint: Pubkey) -> Pubkey {
+        let curve = get_bonding_curve_pda(&self.config, &mint);
+        self.curve_to_mint.insert(curve, mint);
+        self.mint_to_curve.insert(mint, curve);
+        curve
+    }
+
+    /// Records a pair learned elsewhere, rejecting it unless `curve` is the PDA of `mint`.
+    pub fn insert_pair(&mut self, mint: Pubkey, curve: Pubkey) -> Result<(), ErrorCode> {
+        if get_bonding_curve_pda(&self.config, &mint) != curve {
+            return Err(ErrorCode::MintDoesNotMatchBondingCurve);
+        }
+        self.insert(mint);
+        Ok(())
+    }
+
+    pub fn mint_for(&self, curve: &Pubkey) -> Option<Pubkey> {
+        self.curve_to_mint.get(curve).copied()
+    }
+
+    pub fn curve_for(&self, mint: &Pubkey) -> Option<Pubkey> {
+        self.mint_to_curve.get(mint).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.curve_to_mint.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.curve_to_mint.is_empty()
+    }
+
+    /// Writes the index back to the file it was opened from; in-memory indexes
+    /// are left alone. The file is written beside the index and renamed over
+    /// it, so a crash mid-write leaves the previous index intact.
+    pub fn save(&mut self) -> Result<(), ErrorCode> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let contents: String = self
+            .curve_to_mint
+            .iter()
+            .map(|(curve, mint)| format!("{} {}\n", curve, mint))
+            .collect();
+        let mut temp = path.clone().into_os_string();
+        temp.push(".tmp");
+        fs::write(&temp, contents).map_err(|_| ErrorCode::MintIndexIo)?;
+        fs::rename(&temp, path).map_err(|_| ErrorCode::MintIndexIo)?;
+        self.unsaved = 0;
+        Ok(())
+    }
+
+    /// Returns the mint behind `curve`, asking the chain on a cache miss.
+    /// What it learns is saved every `SAVE_BATCH` misses.
+    pub fn resolve(&mut self, rpc_client: &RpcClient, curve: &Pubkey) -> Result<Pubkey, ErrorCode> {
+        if let Some(mint) = self.mint_for(curve) {
+            return Ok(mint);
+        }
+        let mint = resolve_mint(rpc_client, &self.config, curve)?;
+        self.insert(mint);
+        self.unsaved += 1;
+        if self.unsaved >= SAVE_BATCH {
+            self.save()?;
+        }
+        Ok(mint)
+    }
+}
+
+impl Drop for MintIndex {
+    fn drop(&mut self) {
+        if self.unsaved > 0 {
+            let _ = self.save();
+        }
+    }
+}
+
+/// Finds the mint of a bonding curve. The curve's own token account (its
+/// associated bonding curve ATA) names the mint; if the curve holds no token
+/// account any more, the `CreateEvent` of its first transaction is replayed.
+pub fn resolve_mint(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    curve: &Pubkey,
+) -> Result<Pubkey, ErrorCode> {
+    let mint = match mint_from_token_accounts(rpc_client, config, curve) {
+        Some(mint) => mint,
+        None => mint_from_create_event(rpc_client, config, curve)?,
+    };
+
+    if get_bonding_curve_pda(config, &mint) != *curve {
+        return Err(ErrorCode::MintDoesNotMatchBondingCurve);
+    }
+    Ok(mint)
+}
+
+fn mint_from_token_accounts(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    curve: &Pubkey,
+) -> Option<Pubkey> {
+    // The curve's token account lives under whichever program owns the mint.
+    [TOKEN_PROGRAM, TOKEN_2022_PROGRAM]
+        .into_iter()
+        .filter_map(|token_program| {
+            rpc_client
+                .get_token_accounts_by_owner(curve, TokenAccountsFilter::ProgramId(token_program))
+                .ok()
+        })
+        .flatten()
+        .filter_map(|keyed| match keyed.account.data {
+            UiAccountData::Json(parsed) => parsed.parsed["info"]["mint"]
+                .as_str()
+                .and_then(|mint| Pubkey::from_str(mint).ok()),
+            _ => None,
+        })
+        .find(|mint| get_bonding_curve_pda(config, mint) == *curve)
+}
+
+fn mint_from_create_event(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    curve: &Pubkey,
+) -> Result<Pubkey, ErrorCode> {
+    // Signatures come newest first, so page back until the history runs out;
+    // a history longer than `MAX_SIGNATURE_PAGES` pages is given up on.
+    let mut before = None;
+    let mut oldest = None;
+    let mut reached_start = false;
+    for _ in 0..MAX_SIGNATURE_PAGES {
+        let page = rpc_client
+            .get_signatures_for_address_with_config(
+                curve,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    limit: Some(SIGNATURE_PAGE_LIMIT),
+                    ..GetConfirmedSignaturesForAddress2Config::default()
+                },
+            )
+            .map_err(|_| ErrorCode::AccountFetchFailed)?;
+        let Some(last) = page.last() else {
+            reached_start = true;
+            break;
+        };
+        let signature =
+            Signature::from_str(&last.signature).map_err(|_| ErrorCode::DeserializationError)?;
+        oldest = Some(signature);
+        before = Some(signature);
+        if page.len() < SIGNATURE_PAGE_LIMIT {
+            reached_start = true;
+            break;
+        }
+    }
+    if !reached_start {
+        return Err(ErrorCode::MintNotFound);
+    }
+
+    let signature = oldest.ok_or(ErrorCode::MintNotFound)?;
+    fetch_transaction_events::<CreateEvent>(
+        rpc_client,
+        config,
+        &signature,
+        rpc_client.commitment(),
+    )?
+    .into_iter()
+    .find(|event| event.bonding_curve == *curve)
+    .map(|event| event.mint)
+    .ok_or(ErrorCode::MintNotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mint_index_round_trip() {
+        let path = std::env::temp_dir().join(format!("mint-index-{}.txt", Pubkey::new_unique()));
+        let mint = Pubkey::new_unique();
+
+        let mut index = MintIndex::open(&path, &PumpConfig::default()).unwrap();
+        let curve = index.insert(mint);
+        assert!(matches!(
+            index.insert_pair(Pubkey::new_unique(), curve),
+            Err(ErrorCode::MintDoesNotMatchBondingCurve)
+        ));
+        index.save().unwrap();
+
+        let reopened = MintIndex::open(&path, &PumpConfig::default()).unwrap();
+        assert_eq!(reopened.len(), 1);
+        assert_eq!(reopened.mint_for(&curve), Some(mint));
+        assert_eq!(reopened.curve_for(&mint), Some(curve));
+
+        fs::remove_file(path).unwrap();
+    }
+}
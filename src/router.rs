@@ -0,0 +1,265 @@
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::{
+    config::PumpConfig,
+    errors::ErrorCode,
+    fetch::{fetch_bonding_curve, fetch_global},
+    instructions::{buy_ix, sell_ix, Buy, BuyAccounts, Sell, SellAccounts},
+    pump_amm::{self, PoolSnapshot},
+    states::BondingCurve,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapSide {
+    /// `amount` is the SOL, in lamports, to spend.
+    Buy,
+    /// `amount` is the number of tokens, in base units, to sell.
+    Sell,
+}
+
+pub struct SwapAccounts {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+}
+
+#[derive(Debug)]
+pub enum SwapLeg {
+    BondingCurve {
+        token_amount: u64,
+        sol_amount: u64,
+        instructions: Vec<Instruction>,
+    },
+    PumpAmm {
+        token_amount: u64,
+        sol_amount: u64,
+        instructions: Vec<Instruction>,
+    },
+    /// SOL left over after a buy empties the curve. The pool only exists
+    /// once the curve has been migrated, so route it with another `swap` then.
+    PendingMigration { sol_amount: u64 },
+}
+
+#[derive(Debug)]
+pub struct SwapPlan {
+    pub legs: Vec<SwapLeg>,
+}
+
+impl SwapPlan {
+    /// Instructions of every leg that can be sent right away, in order.
+    pub fn instructions(&self) -> Vec<Instruction> {
+        self.legs
+            .iter()
+            .flat_map(|leg| match leg {
+                SwapLeg::BondingCurve { instructions, .. }
+                | SwapLeg::PumpAmm { instructions, .. } => instructions.clone(),
+                SwapLeg::PendingMigration { .. } => vec![],
+            })
+            .collect()
+    }
+}
+
+/// How a SOL-denominated buy splits across the curve.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CurveBuySplit {
+    pub token_amount: u64,
+    pub sol_cost: u64,
+    /// SOL that does not fit on the curve and has to go to the pool.
+    pub remainder: u64,
+}
+
+/// Splits spending `sol_amount` on the curve. When the SOL buys more than
+/// the curve has left, the curve leg takes every remaining token and the
+/// rest of the SOL is carried over.
+pub fn split_curve_buy(
+    curve: &BondingCurve,
+    sol_amount: u64,
+    fee_basis_points: u64,
+) -> Result<CurveBuySplit, ErrorCode> {
+    let token_amount = curve.buy_quote(sol_amount, fee_basis_points);
+    if token_amount < curve.real_token_reserves {
+        return Ok(CurveBuySplit {
+            token_amount,
+            sol_cost: sol_amount,
+            remainder: 0,
+        });
+    }
+
+    let token_amount = curve.real_token_reserves;
+    let sol_cost = curve
+        .buy_cost(token_amount, fee_basis_points)?
+        .min(sol_amount);
+    Ok(CurveBuySplit {
+        token_amount,
+        sol_cost,
+        remainder: sol_amount - sol_cost,
+    })
+}
+
+/// Routes a swap to the bonding curve while it is active and to the pump
+/// AMM pool once it has completed.
+pub fn swap(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    accounts: SwapAccounts,
+    side: SwapSide,
+    amount: u64,
+    slippage: i64,
+) -> Result<SwapPlan, ErrorCode> {
+    let curve = fetch_bonding_curve(rpc_client, config, &accounts.mint)?;
+
+    if curve.complete {
+        return amm_swap(rpc_client, config, accounts, side, amount, slippage)
+            .map(|leg| SwapPlan { legs: vec![leg] });
+    }
+
+    let fee_basis_points = fetch_global(rpc_client, config)?.trade_fee_basis_points(&curve.creator);
+
+    let legs = match side {
+        SwapSide::Buy => {
+            let split = split_curve_buy(&curve, amount, fee_basis_points)?;
+            let instructions = buy_ix(
+                rpc_client,
+                config,
+                BuyAccounts {
+                    mint: accounts.mint,
+                    user: accounts.user,
+                },
+                Buy {
+                    amount: split.token_amount,
+                    max_sol_cost: split.sol_cost,
+                    slippage,
+                },
+            )?;
+            let mut legs = vec![SwapLeg::BondingCurve {
+                token_amount: split.token_amount,
+                sol_amount: split.sol_cost,
+                instructions,
+            }];
+            if split.remainder > 0 {
+                legs.push(SwapLeg::PendingMigration {
+                    sol_amount: split.remainder,
+                });
+            }
+            legs
+        }
+        SwapSide::Sell => {
+            let min_sol_output = curve.sell_quote(amount, fee_basis_points)?;
+            let instructions = sell_ix(
+                rpc_client,
+                config,
+                SellAccounts {
+                    mint: accounts.mint,
+                    user: accounts.user,
+                },
+                Sell {
+                    amount,
+                    min_sol_output,
+                    slippage,
+                },
+            )?;
+            vec![SwapLeg::BondingCurve {
+                token_amount: amount,
+                sol_amount: min_sol_output,
+                instructions,
+            }]
+        }
+    };
+
+    Ok(SwapPlan { legs })
+}
+
+fn amm_swap(
+    rpc_client: &RpcClient,
+    config: &PumpConfig,
+    accounts: SwapAccounts,
+    side: SwapSide,
+    amount: u64,
+    slippage: i64,
+) -> Result<SwapLeg, ErrorCode> {
+    let snapshot = PoolSnapshot::fetch(rpc_client, config, &accounts.mint)?;
+
+    let (token_amount, sol_amount, instructions) = match side {
+        SwapSide::Buy => {
+            let token_amount = snapshot.buy_base_output(amount)?;
+            let instructions = pump_amm::buy_ix(
+                rpc_client,
+                config,
+                BuyAccounts {
+                    mint: accounts.mint,
+                    user: accounts.user,
+                },
+                Buy {
+                    amount: token_amount,
+                    max_sol_cost: amount,
+                    slippage,
+                },
+            )?;
+            (token_amount, amount, instructions)
+        }
+        SwapSide::Sell => {
+            let min_sol_output = snapshot.sell_quote_output(amount)?;
+            let instructions = pump_amm::sell_ix(
+                rpc_client,
+                config,
+                SellAccounts {
+                    mint: accounts.mint,
+                    user: accounts.user,
+                },
+                Sell {
+                    amount,
+                    min_sol_output,
+                    slippage,
+                },
+            )?;
+            (amount, min_sol_output, instructions)
+        }
+    };
+
+    Ok(SwapLeg::PumpAmm {
+        token_amount,
+        sol_amount,
+        instructions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(real_token_reserves: u64) -> BondingCurve {
+        BondingCurve {
+            virtual_token_reserves: 279_900_000_000_000 + real_token_reserves,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+            creator: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn test_split_curve_buy_within_curve() {
+        let split = split_curve_buy(&curve(793_100_000_000_000), 1_000_000_000, 100).unwrap();
+        assert!(split.token_amount > 0);
+        assert_eq!(split.sol_cost, 1_000_000_000);
+        assert_eq!(split.remainder, 0);
+    }
+
+    #[test]
+    fn test_split_curve_buy_crossing_completion() {
+        let nearly_done = curve(1_000_000_000_000);
+        let split = split_curve_buy(&nearly_done, 50_000_000_000, 100).unwrap();
+
+        assert_eq!(split.token_amount, nearly_done.real_token_reserves);
+        assert_eq!(
+            split.sol_cost,
+            nearly_done
+                .buy_cost(nearly_done.real_token_reserves, 100)
+                .unwrap()
+        );
+        assert_eq!(split.sol_cost + split.remainder, 50_000_000_000);
+        assert!(split.remainder > 0);
+    }
+}
@@ -0,0 +1,392 @@
+//! `#[serde(with = ...)]` helpers used by the `serde` feature. Pubkeys are
+//! written as base58 strings. u64 values are written as numbers, or as
+//! strings through [`AsStringU64`] or [`u64_as_string`]; both forms are
+//! accepted when reading.
+
+use serde::{
+    ser::{
+        SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
+    },
+    Serialize, Serializer,
+};
+
+/// Serializes the wrapped value with every u64 in it written as a string,
+/// for JSON consumers without 64-bit integers.
+///
+/// ```ignore
+/// let json = serde_json::to_string(&AsStringU64(&curve))?;
+/// ```
+pub struct AsStringU64<T>(pub T);
+
+impl<T: Serialize> Serialize for AsStringU64<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(StringU64Serializer(serializer))
+    }
+}
+
+/// Forwards to the wrapped serializer, writing u64s as strings and wrapping
+/// every nested value in [`AsStringU64`].
+struct StringU64Serializer<S>(S);
+
+/// A compound serializer whose elements are wrapped in [`AsStringU64`].
+struct StringU64Compound<C>(C);
+
+macro_rules! forward {
+    ($($method:ident($type:ty)),* $(,)?) => {
+        $(fn $method(self, value: $type) -> Result<S::Ok, S::Error> {
+            self.0.$method(value)
+        })*
+    };
+}
+
+impl<S: Serializer> Serializer for StringU64Serializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = StringU64Compound<S::SerializeSeq>;
+    type SerializeTuple = StringU64Compound<S::SerializeTuple>;
+    type SerializeTupleStruct = StringU64Compound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = StringU64Compound<S::SerializeTupleVariant>;
+    type SerializeMap = StringU64Compound<S::SerializeMap>;
+    type SerializeStruct = StringU64Compound<S::SerializeStruct>;
+    type SerializeStructVariant = StringU64Compound<S::SerializeStructVariant>;
+
+    forward!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    );
+
+    fn serialize_u64(self, value: u64) -> Result<S::Ok, S::Error> {
+        self.0.collect_str(&value)
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_none()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.0.serialize_some(&AsStringU64(value))
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_struct(name, &AsStringU64(value))
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0
+            .serialize_newtype_variant(name, variant_index, variant, &AsStringU64(value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        self.0.serialize_seq(len).map(StringU64Compound)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        self.0.serialize_tuple(len).map(StringU64Compound)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        self.0
+            .serialize_tuple_struct(name, len)
+            .map(StringU64Compound)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        self.0
+            .serialize_tuple_variant(name, variant_index, variant, len)
+            .map(StringU64Compound)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        self.0.serialize_map(len).map(StringU64Compound)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        self.0.serialize_struct(name, len).map(StringU64Compound)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        self.0
+            .serialize_struct_variant(name, variant_index, variant, len)
+            .map(StringU64Compound)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+macro_rules! compound {
+    ($($trait:ident::$method:ident),* $(,)?) => {
+        $(impl<C: $trait> $trait for StringU64Compound<C> {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn $method<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+                self.0.$method(&AsStringU64(value))
+            }
+
+            fn end(self) -> Result<C::Ok, C::Error> {
+                self.0.end()
+            }
+        })*
+    };
+}
+
+compound!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+);
+
+impl<C: SerializeMap> SerializeMap for StringU64Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), C::Error> {
+        self.0.serialize_key(&AsStringU64(key))
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_value(&AsStringU64(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+macro_rules! compound_fields {
+    ($($trait:ident),* $(,)?) => {
+        $(impl<C: $trait> $trait for StringU64Compound<C> {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn serialize_field<T: ?Sized + Serialize>(
+                &mut self,
+                key: &'static str,
+                value: &T,
+            ) -> Result<(), C::Error> {
+                self.0.serialize_field(key, &AsStringU64(value))
+            }
+
+            fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+                self.0.skip_field(key)
+            }
+
+            fn end(self) -> Result<C::Ok, C::Error> {
+                self.0.end()
+            }
+        })*
+    };
+}
+
+compound_fields!(SerializeStruct, SerializeStructVariant);
+
+/// A pubkey as a base58 string.
+pub mod pubkey {
+    use std::str::FromStr;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Pubkey::from_str(&encoded).map_err(D::Error::custom)
+    }
+}
+
+/// A fixed-size array of pubkeys as a list of base58 strings.
+pub mod pubkey_array {
+    use std::str::FromStr;
+
+    use serde::{de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        pubkeys: &[Pubkey; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(N))?;
+        for pubkey in pubkeys {
+            seq.serialize_element(&pubkey.to_string())?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[Pubkey; N], D::Error> {
+        let encoded = Vec::<String>::deserialize(deserializer)?;
+        let pubkeys = encoded
+            .iter()
+            .map(|pubkey| Pubkey::from_str(pubkey).map_err(D::Error::custom))
+            .collect::<Result<Vec<_>, _>>()?;
+        pubkeys
+            .try_into()
+            .map_err(|pubkeys: Vec<Pubkey>| D::Error::invalid_length(pubkeys.len(), &"N pubkeys"))
+    }
+}
+
+/// A u64 as a number; written as a string under [`AsStringU64`].
+pub mod u64 {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(u64),
+        String(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(*value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        match NumberOrString::deserialize(deserializer)? {
+            NumberOrString::Number(value) => Ok(value),
+            NumberOrString::String(value) => value.parse().map_err(D::Error::custom),
+        }
+    }
+}
+
+/// A u64 always written as a string, for fields of the caller's own types;
+/// numbers and strings are both accepted when reading.
+pub mod u64_as_string {
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        super::u64::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::AsStringU64;
+    use crate::{instructions::buy::BuyAccounts, states::BondingCurve};
+
+    #[test]
+    fn test_bonding_curve_json() {
+        let curve = BondingCurve {
+            virtual_token_reserves: u64::MAX,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+            creator: Pubkey::new_unique(),
+        };
+        let value = serde_json::to_value(&curve).unwrap();
+        assert_eq!(value["creator"], json!(curve.creator.to_string()));
+        assert_eq!(value["virtual_token_reserves"], json!(u64::MAX));
+
+        let value = serde_json::to_value(AsStringU64(&curve)).unwrap();
+        assert_eq!(value["virtual_token_reserves"], json!(u64::MAX.to_string()));
+        assert_eq!(
+            serde_json::to_value(&curve).unwrap()["virtual_token_reserves"],
+            json!(u64::MAX)
+        );
+
+        let decoded: BondingCurve = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.virtual_token_reserves, u64::MAX);
+        assert_eq!(decoded.creator, curve.creator);
+    }
+
+    #[test]
+    fn test_accepts_numbers_and_strings() {
+        let mint = Pubkey::new_unique();
+        let accounts: BuyAccounts = serde_json::from_value(json!({
+            "mint": mint.to_string(),
+            "user": Pubkey::default().to_string(),
+        }))
+        .unwrap();
+        assert_eq!(accounts.mint, mint);
+
+        let curve = json!({
+            "virtual_token_reserves": "1",
+            "virtual_sol_reserves": 2,
+            "real_token_reserves": "3",
+            "real_sol_reserves": 4,
+            "token_total_supply": "5",
+            "complete": true,
+            "creator": mint.to_string(),
+        });
+        let decoded: BondingCurve = serde_json::from_value(curve).unwrap();
+        assert_eq!(decoded.virtual_token_reserves, 1);
+        assert_eq!(decoded.virtual_sol_reserves, 2);
+        assert!(
+            serde_json::from_value::<BuyAccounts>(json!({ "mint": "not base58", "user": "" }))
+                .is_err()
+        );
+    }
+}
@@ -1,13 +1,307 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+
+use crate::{
+    constants::{INITIAL_REAL_TOKEN_RESERVES, TOKEN_DECIMALS},
+    errors::ErrorCode,
+    states::Global,
+};
+
+const BASIS_POINTS: u128 = 10_000;
 
 #[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BondingCurve {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub virtual_token_reserves: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub virtual_sol_reserves: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub real_token_reserves: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub real_sol_reserves: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub token_total_supply: u64,
     pub complete: bool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub creator: Pubkey,
 }
+
+impl BondingCurve {
+    pub const DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+    /// Size of an account holding exactly the fields above.
+    pub const LEN: usize = 8 + 8 * 5 + 1 + 32;
+    /// Size of a curve created before `creator` was added and never extended.
+    pub const LEGACY_LEN: usize = 8 + 8 * 5 + 1;
+    /// Size of an account grown by the program's `extend_account` instruction.
+    pub const EXTENDED_LEN: usize = 150;
+    /// Byte offset of `complete` within the account data.
+    pub const COMPLETE_OFFSET: usize = 8 + 8 * 5;
+    /// Byte offset of `creator` within the account data.
+    pub const CREATOR_OFFSET: usize = Self::COMPLETE_OFFSET + 1;
+
+    /// Decodes a raw bonding curve account, discriminator included. A
+    /// legacy curve without a `creator` decodes with `Pubkey::default()`.
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ErrorCode> {
+        let payload = data
+            .strip_prefix(Self::DISCRIMINATOR.as_slice())
+            .ok_or(ErrorCode::DeserializationError)?;
+        if data.len() == Self::LEGACY_LEN {
+            let padded = [payload, Pubkey::default().as_ref()].concat();
+            return Self::deserialize(&mut &padded[..])
+                .map_err(|_| ErrorCode::DeserializationError);
+        }
+        Self::deserialize(&mut &payload[..]).map_err(|_| ErrorCode::DeserializationError)
+    }
+
+    /// The curve the program creates for a new mint under `global`'s parameters.
+    pub fn initial(global: &Global, creator: Pubkey) -> Self {
+        Self {
+            virtual_token_reserves: global.initial_virtual_token_reserves,
+            virtual_sol_reserves: global.initial_virtual_sol_reserves,
+            real_token_reserves: global.initial_real_token_reserves,
+            real_sol_reserves: 0,
+            token_total_supply: global.token_total_supply,
+            complete: false,
+            creator,
+        }
+    }
+
+    /// Moves the reserves as the program does after a buy of `token_amount`,
+    /// returning the SOL that went into the curve (fees excluded).
+    pub fn apply_buy(&mut self, token_amount: u64) -> Result<u64, ErrorCode> {
+        self.validate_buy(token_amount)?;
+        let sol_cost = self.buy_cost(token_amount, 0)?;
+        self.virtual_token_reserves -= token_amount;
+        self.real_token_reserves -= token_amount;
+        self.virtual_sol_reserves = self
+            .virtual_sol_reserves
+            .checked_add(sol_cost)
+            .ok_or(ErrorCode::Overflow)?;
+        self.real_sol_reserves = self
+            .real_sol_reserves
+            .checked_add(sol_cost)
+            .ok_or(ErrorCode::Overflow)?;
+        if self.real_token_reserves == 0 {
+            self.complete = true;
+        }
+        Ok(sol_cost)
+    }
+
+    /// Spot price of one whole token in SOL, from the virtual reserves.
+    pub fn price_in_sol(&self) -> f64 {
+        price_from_reserves(self.virtual_sol_reserves, self.virtual_token_reserves)
+    }
+
+    /// Fraction of the curve sold so far, from 0.0 to 1.0.
+    pub fn progress(&self) -> f64 {
+        if self.complete {
+            return 1.0;
+        }
+        let sold = INITIAL_REAL_TOKEN_RESERVES.saturating_sub(self.real_token_reserves);
+        sold as f64 / INITIAL_REAL_TOKEN_RESERVES as f64
+    }
+
+    /// SOL, fees included, the program charges for buying `token_amount`.
+    pub fn buy_cost(&self, token_amount: u64, fee_basis_points: u64) -> Result<u64, ErrorCode> {
+        if token_amount == 0 {
+            return Ok(0);
+        }
+        if token_amount >= self.virtual_token_reserves {
+            return Err(ErrorCode::NotEnoughTokensToBuy);
+        }
+        let sol_cost = (token_amount as u128)
+            .checked_mul(self.virtual_sol_reserves as u128)
+            .ok_or(ErrorCode::Overflow)?
+            / (self.virtual_token_reserves - token_amount) as u128
+            + 1;
+        let fee = (sol_cost * fee_basis_points as u128).div_ceil(BASIS_POINTS);
+        (sol_cost + fee).try_into().map_err(|_| ErrorCode::Overflow)
+    }
+
+    /// Tokens received for spending `sol_amount`, fees included, capped at
+    /// what is left on the curve.
+    pub fn buy_quote(&self, sol_amount: u64, fee_basis_points: u64) -> u64 {
+        let sol_in = sol_amount as u128 * BASIS_POINTS / (BASIS_POINTS + fee_basis_points as u128);
+        let denominator = self.virtual_sol_reserves as u128 + sol_in;
+        if denominator == 0 {
+            return 0;
+        }
+        let tokens = self.virtual_token_reserves as u128 * sol_in / denominator;
+        (tokens as u64).min(self.real_token_reserves)
+    }
+
+    /// SOL received, after fees, for selling `token_amount`.
+    pub fn sell_quote(&self, token_amount: u64, fee_basis_points: u64) -> Result<u64, ErrorCode> {
+        let denominator = self.virtual_token_reserves as u128 + token_amount as u128;
+        if denominator == 0 {
+            return Ok(0);
+        }
+        let sol_out = (token_amount as u128)
+            .checked_mul(self.virtual_sol_reserves as u128)
+            .ok_or(ErrorCode::Overflow)?
+            / denominator;
+        let fee = (sol_out * fee_basis_points as u128).div_ceil(BASIS_POINTS);
+        sol_out
+            .saturating_sub(fee)
+            .try_into()
+            .map_err(|_| ErrorCode::Overflow)
+    }
+
+    /// Mirrors the on-chain checks the program runs before a buy, so the
+    /// failure surfaces before the transaction is sent.
+    pub fn validate_buy(&self, amount: u64) -> Result<(), ErrorCode> {
+        if self.complete {
+            return Err(ErrorCode::BondingCurveComplete);
+        }
+        if amount == 0 {
+            return Err(ErrorCode::BuyZeroAmount);
+        }
+        if amount > self.real_token_reserves {
+            return Err(ErrorCode::NotEnoughTokensToBuy);
+        }
+        Ok(())
+    }
+
+    /// Mirrors the on-chain checks the program runs before a sell.
+    /// `user_balance` is the raw token balance of the seller's ATA.
+    pub fn validate_sell(&self, amount: u64, user_balance: u64) -> Result<(), ErrorCode> {
+        if self.complete {
+            return Err(ErrorCode::BondingCurveComplete);
+        }
+        if amount == 0 {
+            return Err(ErrorCode::SellZeroAmount);
+        }
+        if amount > user_balance {
+            return Err(ErrorCode::NotEnoughTokensToSell);
+        }
+        Ok(())
+    }
+}
+
+/// Spot price of one whole token in SOL for a curve holding
+/// `virtual_sol_reserves` lamports against `virtual_token_reserves` raw
+/// tokens; 0 when there are no tokens.
+pub fn price_from_reserves(virtual_sol_reserves: u64, virtual_token_reserves: u64) -> f64 {
+    if virtual_token_reserves == 0 {
+        return 0.0;
+    }
+    let sol = virtual_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
+    let tokens = virtual_token_reserves as f64 / 10f64.powi(TOKEN_DECIMALS as i32);
+    sol / tokens
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Mainnet launch parameters, shared by tests across the crate.
+    pub(crate) fn global() -> Global {
+        Global {
+            initialized: true,
+            authority: Pubkey::new_unique(),
+            fee_recipient: Pubkey::new_unique(),
+            initial_virtual_token_reserves: 1_073_000_000_000_000,
+            initial_virtual_sol_reserves: 30_000_000_000,
+            initial_real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+            fee_basis_points: 95,
+            withdraw_authority: Pubkey::new_unique(),
+            enable_migrate: true,
+            pool_migration_fee: 15_000_001,
+            creator_fee_basis_points: 5,
+            fee_recipients: [Pubkey::new_unique(); 7],
+            set_creator_authority: Pubkey::new_unique(),
+        }
+    }
+
+    fn curve(complete: bool) -> BondingCurve {
+        BondingCurve {
+            virtual_token_reserves: 1_073_000_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete,
+            creator: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn test_account_layout_offsets() {
+        let complete = curve(true);
+        let mut data = BondingCurve::DISCRIMINATOR.to_vec();
+        complete.serialize(&mut data).unwrap();
+
+        assert_eq!(data.len(), BondingCurve::LEN);
+        assert_eq!(data[BondingCurve::COMPLETE_OFFSET], 1);
+        assert_eq!(
+            &data[BondingCurve::CREATOR_OFFSET..],
+            complete.creator.as_ref()
+        );
+        assert!(BondingCurve::from_account_data(&data).is_ok());
+
+        let legacy = BondingCurve::from_account_data(&data[..BondingCurve::LEGACY_LEN]).unwrap();
+        assert!(legacy.complete);
+        assert_eq!(legacy.creator, Pubkey::default());
+    }
+
+    #[test]
+    fn test_price_and_progress() {
+        let fresh = curve(false);
+        assert!((fresh.price_in_sol() - 30.0 / 1_073_000_000.0).abs() < 1e-15);
+        assert_eq!(fresh.progress(), 0.0);
+        assert_eq!(curve(true).progress(), 1.0);
+    }
+
+    #[test]
+    fn test_quotes_agree_with_cost() {
+        let fresh = curve(false);
+        let tokens = fresh.buy_quote(1_000_000_000, 100);
+        let cost = fresh.buy_cost(tokens, 100).unwrap();
+
+        // Buying what 1 SOL quotes for never costs more than 1 SOL plus rounding.
+        assert!(cost <= 1_000_000_000 + 2);
+        assert!(fresh.sell_quote(tokens, 100).unwrap() < 1_000_000_000);
+        assert_eq!(
+            fresh.buy_quote(u64::MAX / 2, 100),
+            fresh.real_token_reserves
+        );
+    }
+
+    #[test]
+    fn test_validate_buy() {
+        let active = curve(false);
+        assert!(active.validate_buy(1_000_000).is_ok());
+        assert!(matches!(
+            active.validate_buy(0),
+            Err(ErrorCode::BuyZeroAmount)
+        ));
+        assert!(matches!(
+            active.validate_buy(active.real_token_reserves + 1),
+            Err(ErrorCode::NotEnoughTokensToBuy)
+        ));
+        assert!(matches!(
+            curve(true).validate_buy(1_000_000),
+            Err(ErrorCode::BondingCurveComplete)
+        ));
+    }
+
+    #[test]
+    fn test_validate_sell() {
+        let active = curve(false);
+        assert!(active.validate_sell(500, 1_000).is_ok());
+        assert!(matches!(
+            active.validate_sell(0, 1_000),
+            Err(ErrorCode::SellZeroAmount)
+        ));
+        assert!(matches!(
+            active.validate_sell(1_001, 1_000),
+            Err(ErrorCode::NotEnoughTokensToSell)
+        ));
+        assert!(matches!(
+            curve(true).validate_sell(500, 1_000),
+            Err(ErrorCode::BondingCurveComplete)
+        ));
+    }
+}
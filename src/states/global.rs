@@ -1,20 +1,56 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_sdk::pubkey::Pubkey;
 
+use crate::errors::ErrorCode;
+
 #[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Global {
     pub initialized: bool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub authority: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub fee_recipient: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub initial_virtual_token_reserves: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub initial_virtual_sol_reserves: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub initial_real_token_reserves: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub token_total_supply: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub fee_basis_points: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub withdraw_authority: Pubkey,
     pub enable_migrate: bool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub pool_migration_fee: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
     pub creator_fee_basis_points: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey_array"))]
     pub fee_recipients: [Pubkey; 7],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub set_creator_authority: Pubkey,
 }
+
+impl Global {
+    pub const DISCRIMINATOR: [u8; 8] = [167, 232, 232, 177, 200, 108, 114, 127];
+
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ErrorCode> {
+        let payload = data
+            .strip_prefix(Self::DISCRIMINATOR.as_slice())
+            .ok_or(ErrorCode::DeserializationError)?;
+        Self::deserialize(&mut &payload[..]).map_err(|_| ErrorCode::DeserializationError)
+    }
+
+    /// Protocol plus creator fee charged on a trade against a curve launched
+    /// by `creator`. Curves without a creator pay no creator fee.
+    pub fn trade_fee_basis_points(&self, creator: &Pubkey) -> u64 {
+        if *creator == Pubkey::default() {
+            self.fee_basis_points
+        } else {
+            self.fee_basis_points + self.creator_fee_basis_points
+        }
+    }
+}
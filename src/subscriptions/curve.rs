@@ -0,0 +1,200 @@
+use std::sync::{mpsc::Sender, Arc};
+
+use solana_account_decoder_client_types::{UiAccount, UiAccountEncoding};
+use solana_client::{
+    pubsub_client::PubsubClient, rpc_client::RpcClient, rpc_config::RpcAccountInfoConfig,
+    rpc_response::Response,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+
+use crate::{
+    config::PumpConfig, errors::ErrorCode, pda::get_bonding_curve_pda, states::BondingCurve,
+};
+
+use super::{spawn_with_reconnect, Subscription};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CurveSnapshot {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::u64"))]
+    pub slot: u64,
+    pub bonding_curve: BondingCurve,
+    pub price_in_sol: f64,
+    pub progress: f64,
+}
+
+#[derive(Debug)]
+pub enum CurveUpdate {
+    Snapshot(CurveSnapshot),
+    /// Sent once when the curve flips to `complete`; the stream ends after it.
+    Completed(CurveSnapshot),
+}
+
+pub type CurveSubscription = Subscription<CurveUpdate>;
+
+/// Subscribes to the bonding curve of `mint`.
+/// On every (re)connect the curve is first read through `rpc_client`, so an
+/// update missed while the socket was down, `Completed` included, is still
+/// delivered.
+pub fn subscribe_curve(
+    ws_url: &str,
+    config: &PumpConfig,
+    rpc_client: Arc<RpcClient>,
+    mint: &Pubkey,
+    commitment: CommitmentConfig,
+) -> Result<CurveSubscription, ErrorCode> {
+    let bonding_curve = get_bonding_curve_pda(config, mint);
+    let account_config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(commitment),
+        ..RpcAccountInfoConfig::default()
+    };
+    let ws_url = ws_url.to_string();
+
+    spawn_with_reconnect(
+        move || {
+            PubsubClient::account_subscribe(&ws_url, &bonding_curve, Some(account_config.clone()))
+                .ok()
+        },
+        move |sender| {
+            let Ok(response) = rpc_client.get_account_with_commitment(&bonding_curve, commitment)
+            else {
+                return true;
+            };
+            match response.value {
+                Some(account) => forward_update(sender, response.context.slot, &account.data),
+                None => true,
+            }
+        },
+        |sender, response: Response<UiAccount>| match response.value.data.decode() {
+            Some(data) => forward_update(sender, response.context.slot, &data),
+            None => true,
+        },
+    )
+}
+
+/// Decodes one copy of the curve account and forwards it. Returns `false`
+/// once the stream should end, either because the curve completed or nobody
+/// listens.
+fn forward_update(sender: &Sender<CurveUpdate>, slot: u64, data: &[u8]) -> bool {
+    let Ok(bonding_curve) = BondingCurve::from_account_data(data) else {
+        return true;
+    };
+
+    let complete = bonding_curve.complete;
+    let snapshot = CurveSnapshot {
+        slot,
+        price_in_sol: bonding_curve.price_in_sol(),
+        progress: bonding_curve.progress(),
+        bonding_curve,
+    };
+    let update = if complete {
+        CurveUpdate::Completed(snapshot)
+    } else {
+        CurveUpdate::Snapshot(snapshot)
+    };
+
+    sender.send(update).is_ok() && !complete
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subscriptions::tests::spawn_stand_in_server;
+    use ::borsh::BorshSerialize;
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use serde_json::json;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_rpc_client::mock_sender::MocksMap;
+
+    fn curve_account(complete: bool) -> serde_json::Value {
+        let curve = BondingCurve {
+            virtual_token_reserves: 1_073_000_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete,
+            creator: Pubkey::new_unique(),
+        };
+        let mut data = BondingCurve::DISCRIMINATOR.to_vec();
+        curve.serialize(&mut data).unwrap();
+        json!({
+            "lamports": 1,
+            "owner": Pubkey::default().to_string(),
+            "data": [BASE64_STANDARD.encode(&data), "base64"],
+            "executable": false,
+            "rentEpoch": 0,
+            "space": data.len(),
+        })
+    }
+
+    fn curve_notification(slot: u64, complete: bool) -> String {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "accountNotification",
+            "params": {
+                "result": {"context": {"slot": slot}, "value": curve_account(complete)},
+                "subscription": 1,
+            },
+        })
+        .to_string()
+    }
+
+    fn mock_rpc(accounts: Vec<serde_json::Value>) -> Arc<RpcClient> {
+        let mocks = accounts
+            .into_iter()
+            .map(|account| (RpcRequest::GetAccountInfo, account))
+            .collect::<MocksMap>();
+        Arc::new(RpcClient::new_mock_with_mocks_map("succeeds", mocks))
+    }
+
+    #[test]
+    fn test_subscribe_curve_reconnects_until_complete() {
+        let url = spawn_stand_in_server(vec![
+            vec![curve_notification(1, false)],
+            vec![curve_notification(2, true)],
+        ]);
+
+        let subscription = subscribe_curve(
+            &url,
+            &PumpConfig::default(),
+            mock_rpc(vec![]),
+            &Pubkey::new_unique(),
+            CommitmentConfig::confirmed(),
+        )
+        .unwrap();
+        let updates: Vec<CurveUpdate> = subscription.collect();
+
+        assert_eq!(updates.len(), 2);
+        assert!(matches!(&updates[0], CurveUpdate::Snapshot(s) if s.slot == 1));
+        assert!(
+            matches!(&updates[1], CurveUpdate::Completed(s) if s.slot == 2 && s.progress == 1.0)
+        );
+    }
+
+    #[test]
+    fn test_subscribe_curve_resyncs_after_reconnect() {
+        // The curve completes while the socket is down: the second session
+        // never sends it, only the read made on reconnect sees it.
+        let url = spawn_stand_in_server(vec![vec![curve_notification(1, false)], vec![]]);
+        let rpc_client = mock_rpc(vec![
+            json!({"context": {"slot": 1}, "value": null}),
+            json!({"context": {"slot": 5}, "value": curve_account(true)}),
+        ]);
+
+        let subscription = subscribe_curve(
+            &url,
+            &PumpConfig::default(),
+            rpc_client,
+            &Pubkey::new_unique(),
+            CommitmentConfig::confirmed(),
+        )
+        .unwrap();
+        let updates: Vec<CurveUpdate> = subscription.collect();
+
+        assert_eq!(updates.len(), 2);
+        assert!(matches!(&updates[0], CurveUpdate::Snapshot(s) if s.slot == 1));
+        assert!(matches!(&updates[1], CurveUpdate::Completed(s) if s.slot == 5));
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    str::FromStr,
+    sync::Arc,
+};
+
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_client::RpcClient,
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+    rpc_response::{Response, RpcLogsResponse},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+
+use crate::{
+    config::PumpConfig,
+    errors::ErrorCode,
+    events::{fetch_transaction_events, parse_log_events, CreateEvent},
+};
+
+use super::{spawn_with_reconnect, Subscription};
+
+/// Log lines of the `create` and `create_v2` (Token-2022) instructions.
+const CREATE_INSTRUCTION_LOGS: [&str; 2] = [
+    "Program log: Instruction: Create",
+    "Program log: Instruction: CreateV2",
+];
+
+/// Number of recent signatures remembered to drop replays after a reconnect.
+const SEEN_SIGNATURES_CAPACITY: usize = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct LaunchEvent {
+    pub signature: Signature,
+    pub slot: u64,
+    pub event: CreateEvent,
+}
+
+#[derive(Debug)]
+pub enum LaunchUpdate {
+    Launched(Box<LaunchEvent>),
+    /// A `create` whose logs were truncated and whose transaction could not
+    /// be fetched. Its signature is not remembered, so a replay after a
+    /// reconnect is decoded again.
+    FetchFailed {
+        signature: Signature,
+        slot: u64,
+        error: ErrorCode,
+    },
+}
+
+pub type LaunchSubscription = Subscription<LaunchUpdate>;
+
+/// Bounded set of signatures, evicting the oldest once full.
+struct SeenSignatures {
+    set: HashSet<Signature>,
+    order: VecDeque<Signature>,
+}
+
+impl SeenSignatures {
+    fn new() -> Self {
+        Self {
+            set: HashSet::with_capacity(SEEN_SIGNATURES_CAPACITY),
+            order: VecDeque::with_capacity(SEEN_SIGNATURES_CAPACITY),
+        }
+    }
+
+    fn contains(&self, signature: &Signature) -> bool {
+        self.set.contains(signature)
+    }
+
+    /// Returns `true` the first time a signature is inserted.
+    fn insert(&mut self, signature: Signature) -> bool {
+        if !self.set.insert(signature) {
+            return false;
+        }
+        self.order.push_back(signature);
+        if self.order.len() > SEEN_SIGNATURES_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// Subscribes to successful `create` and `create_v2` invocations of the
+/// pump.fun program.
+/// `CreateEvent`s are decoded from the logs; when a node truncates them,
+/// `rpc_client` (if given) is used to fetch the transaction instead, and a
+/// failed fetch is reported as [`LaunchUpdate::FetchFailed`].
+pub fn subscribe_launches(
+    ws_url: &str,
+    config: &PumpConfig,
+    rpc_client: Option<Arc<RpcClient>>,
+    commitment: CommitmentConfig,
+) -> Result<LaunchSubscription, ErrorCode> {
+    let ws_url = ws_url.to_string();
+    let config = *config;
+    let program_id = config.program_id.to_string();
+    let mut seen = SeenSignatures::new();
+
+    spawn_with_reconnect(
+        move || {
+            PubsubClient::logs_subscribe(
+                &ws_url,
+                RpcTransactionLogsFilter::Mentions(vec![program_id.clone()]),
+                RpcTransactionLogsConfig {
+                    commitment: Some(commitment),
+                },
+            )
+            .ok()
+        },
+        |_| true,
+        move |sender, response: Response<RpcLogsResponse>| {
+            let logs = response.value;
+            let is_create = logs
+                .logs
+                .iter()
+                .any(|log| CREATE_INSTRUCTION_LOGS.contains(&log.as_str()));
+            if logs.err.is_some() || !is_create {
+                return true;
+            }
+            let Ok(signature) = Signature::from_str(&logs.signature) else {
+                return true;
+            };
+            if seen.contains(&signature) {
+                return true;
+            }
+            let slot = response.context.slot;
+
+            let mut events = parse_log_events::<CreateEvent>(&config, &logs.logs);
+            if events.is_empty() {
+                if let Some(rpc_client) = &rpc_client {
+                    match fetch_transaction_events(rpc_client, &config, &signature, commitment) {
+                        Ok(fetched) => events = fetched,
+                        Err(error) => {
+                            return sender
+                                .send(LaunchUpdate::FetchFailed {
+                                    signature,
+                                    slot,
+                                    error,
+                                })
+                                .is_ok();
+                        }
+                    }
+                }
+            }
+            if events.is_empty() {
+                return true;
+            }
+
+            // Only a launch that was actually delivered is remembered.
+            let delivered = events.into_iter().all(|event| {
+                sender
+                    .send(LaunchUpdate::Launched(Box::new(LaunchEvent {
+                        signature,
+                        slot,
+                        event,
+                    })))
+                    .is_ok()
+            });
+            if delivered {
+                seen.insert(signature);
+            }
+            delivered
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{events::PumpEvent, subscriptions::tests::spawn_stand_in_server};
+    use ::borsh::BorshSerialize;
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use solana_sdk::pubkey::Pubkey;
+    use std::{sync::mpsc::RecvTimeoutError, time::Duration};
+
+    fn create_notification(signature: &Signature, name: &str, instruction_log: &str) -> String {
+        let event = CreateEvent {
+            name: name.to_string(),
+            symbol: "TEST".to_string(),
+            uri: "https://example.com/metadata.json".to_string(),
+            mint: Pubkey::new_unique(),
+            bonding_curve: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            timestamp: 0,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+        };
+        let mut data = CreateEvent::DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+        format!(
+            r#"{{"jsonrpc":"2.0","method":"logsNotification","params":{{"result":{{"context":{{"slot":7}},"value":{{"signature":"{}","err":null,"logs":["Program {program_id} invoke [1]","{}","{}{}","Program {program_id} success"]}}}},"subscription":1}}}}"#,
+            signature,
+            instruction_log,
+            "Program data: ",
+            BASE64_STANDARD.encode(&data),
+            program_id = PumpConfig::default().program_id,
+        )
+    }
+
+    #[test]
+    fn test_subscribe_launches_dedups_across_reconnects() {
+        let first = Signature::new_unique();
+        let second = Signature::new_unique();
+        let url = spawn_stand_in_server(vec![
+            vec![create_notification(
+                &first,
+                "One",
+                CREATE_INSTRUCTION_LOGS[0],
+            )],
+            vec![
+                create_notification(&first, "One", CREATE_INSTRUCTION_LOGS[0]),
+                create_notification(&second, "Two", CREATE_INSTRUCTION_LOGS[1]),
+            ],
+        ]);
+
+        let subscription = subscribe_launches(
+            &url,
+            &PumpConfig::default(),
+            None,
+            CommitmentConfig::confirmed(),
+        )
+        .unwrap();
+        let receiver = subscription.receiver();
+
+        let timeout = Duration::from_secs(5);
+        let Ok(LaunchUpdate::Launched(launch)) = receiver.recv_timeout(timeout) else {
+            panic!("expected a launch");
+        };
+        assert_eq!(launch.signature, first);
+        assert_eq!(launch.event.name, "One");
+        let Ok(LaunchUpdate::Launched(launch)) = receiver.recv_timeout(timeout) else {
+            panic!("expected a launch");
+        };
+        assert_eq!(launch.signature, second);
+        assert_eq!(launch.event.name, "Two");
+        assert!(matches!(
+            receiver.recv_timeout(Duration::from_millis(500)),
+            Err(RecvTimeoutError::Timeout)
+        ));
+    }
+}
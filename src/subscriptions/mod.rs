@@ -0,0 +1,185 @@
+pub mod curve;
+pub use curve::*;
+
+pub mod launches;
+pub use launches::*;
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use serde::de::DeserializeOwned;
+use solana_client::pubsub_client::PubsubClientSubscription;
+
+use crate::errors::ErrorCode;
+
+/// Delay between reconnect attempts after a websocket drops.
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// How often a worker wakes up to check whether it was shut down.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+type PubsubSubscription<N> = (PubsubClientSubscription<N>, crossbeam_channel::Receiver<N>);
+
+/// A decoded websocket feed. Iterate it, or read from [`Subscription::receiver`],
+/// to receive items; the worker reconnects on its own whenever the socket drops.
+pub struct Subscription<T> {
+    receiver: Receiver<T>,
+    exit: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<T> Subscription<T> {
+    pub fn receiver(&self) -> &Receiver<T> {
+        &self.receiver
+    }
+
+    pub fn shutdown(&mut self) {
+        self.exit.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl<T> Iterator for Subscription<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Runs `handle` on every notification of the subscription produced by
+/// `connect`, reconnecting after drops; `connect` returns `None` on failure.
+/// `on_connect` runs after every successful (re)connect, before any
+/// notification is handled, to catch up on what the socket may have missed.
+/// The first connection is made eagerly so a bad endpoint is reported
+/// instead of retried forever. `on_connect` and `handle` return `false` to
+/// end the feed.
+fn spawn_with_reconnect<N, T, C, O, H>(
+    mut connect: C,
+    mut on_connect: O,
+    mut handle: H,
+) -> Result<Subscription<T>, ErrorCode>
+where
+    N: DeserializeOwned + Send + 'static,
+    T: Send + 'static,
+    C: FnMut() -> Option<PubsubSubscription<N>> + Send + 'static,
+    O: FnMut(&Sender<T>) -> bool + Send + 'static,
+    H: FnMut(&Sender<T>, N) -> bool + Send + 'static,
+{
+    let first = connect().ok_or(ErrorCode::SubscriptionFailed)?;
+
+    let (sender, receiver) = mpsc::channel();
+    let exit = Arc::new(AtomicBool::new(false));
+    let worker_exit = exit.clone();
+
+    let worker = thread::spawn(move || {
+        let mut subscription = Some(first);
+        while !worker_exit.load(Ordering::Relaxed) {
+            let (client, notifications) = match subscription.take().or_else(&mut connect) {
+                Some(subscription) => subscription,
+                None => {
+                    thread::sleep(RECONNECT_DELAY);
+                    continue;
+                }
+            };
+            if !on_connect(&sender) {
+                worker_exit.store(true, Ordering::Relaxed);
+            }
+
+            while !worker_exit.load(Ordering::Relaxed) {
+                match notifications.recv_timeout(POLL_INTERVAL) {
+                    Ok(notification) => {
+                        if !handle(&sender, notification) {
+                            worker_exit.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    Err(err) if err.is_timeout() => continue,
+                    Err(_) => break,
+                }
+            }
+
+            drop(client);
+            if !worker_exit.load(Ordering::Relaxed) {
+                thread::sleep(RECONNECT_DELAY);
+            }
+        }
+    });
+
+    Ok(Subscription {
+        receiver,
+        exit,
+        worker: Some(worker),
+    })
+}
+
+/// Derives the pubsub endpoint from an RPC http(s) URL, following the
+/// Solana CLI convention of bumping the default port 8899 to 8900.
+pub fn websocket_url(rpc_url: &str) -> String {
+    let url = if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        rpc_url.to_string()
+    };
+    url.replacen(":8899", ":8900", 1)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use tungstenite::{accept, Message};
+
+    /// Accepts one websocket client per entry in `sessions`, acknowledges the
+    /// subscription, pushes the given notifications and then hangs up.
+    pub(crate) fn spawn_stand_in_server(sessions: Vec<Vec<String>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for notifications in sessions {
+                let (stream, _) = listener.accept().unwrap();
+                let mut socket = accept(stream).unwrap();
+                socket.read().unwrap();
+                socket
+                    .send(Message::Text(
+                        r#"{"jsonrpc":"2.0","result":1,"id":1}"#.to_string(),
+                    ))
+                    .unwrap();
+                for notification in notifications {
+                    socket.send(Message::Text(notification)).unwrap();
+                }
+                let _ = socket.close(None);
+                let _ = socket.flush();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(
+            websocket_url("https://api.devnet.solana.com"),
+            "wss://api.devnet.solana.com"
+        );
+        assert_eq!(
+            websocket_url("http://127.0.0.1:8899"),
+            "ws://127.0.0.1:8900"
+        );
+    }
+}